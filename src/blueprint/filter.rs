@@ -0,0 +1,243 @@
+// Filters of the blueprint placeholders, e.g. the `lighten 10%` and
+// `fmt rgb` of `{color_04 | lighten 10% | fmt rgb}`.
+
+use crate::colortable::color_format::ColorFormat;
+use crate::colortable::rgb::{MixSpace, RGB};
+use crate::colortable::rgba::RGBA;
+use crate::config::blueprint::directive::COLOR_FORMAT_PRESETS;
+use crate::config::blueprint::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    // Amounts in percent, as in the colorscheme expressions
+    Lighten(f32),
+    Darken(f32),
+    Saturate(f32),
+    Desaturate(f32),
+    // Degrees
+    HueRotate(f32),
+    Invert,
+    Grayscale,
+    ContrastFg,
+    // Opacity in [0, 1]
+    Alpha(f32),
+    // Mix space of the composite color of the placeholder
+    Mix(MixSpace),
+    // Color format preset of the placeholder. Templates aren't allowed,
+    // since placeholders can't hold braces
+    Format(ColorFormat),
+}
+
+impl Filter {
+    pub const NAMES: [&'static str; 11] = [
+        FILTER_LIGHTEN,
+        FILTER_DARKEN,
+        FILTER_SATURATE,
+        FILTER_DESATURATE,
+        FILTER_HUE_ROTATE,
+        FILTER_INVERT,
+        FILTER_GRAYSCALE,
+        FILTER_CONTRAST_FG,
+        FILTER_ALPHA,
+        FILTER_MIX,
+        FILTER_FORMAT,
+    ];
+
+    // Name of a filter, e.g. `lighten` for `lighten 10%`
+    pub fn name(source: &str) -> &str {
+        let source = source.trim();
+        let end = source
+            .find(|c: char| c.is_whitespace() || c == ':')
+            .unwrap_or(source.len());
+        &source[..end]
+    }
+
+    // Parses a filter, e.g. `lighten 10%` or `alpha:80`
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let source = source.trim();
+        let (name, argument) = source.split_at(Self::name(source).len());
+
+        // In the colon notation, the opacity is in percent
+        let argument = match argument.strip_prefix(MIXED_COLOR_FIELD_SEPARATOR) {
+            Some(argument) if name == FILTER_ALPHA && argument.parse::<f32>().is_ok() => {
+                format!("{}%", argument)
+            }
+            Some(argument) => argument.to_string(),
+            None => argument.trim().to_string(),
+        };
+
+        let invalid_argument = |expected: &str| match argument.is_empty() {
+            true => format!(
+                "Missing argument of filter `{}`, expected {}",
+                name, expected
+            ),
+            false => format!(
+                "Invalid argument `{}` of filter `{}`, expected {}",
+                argument, name, expected
+            ),
+        };
+        let percentage = || number(&argument, "%").ok_or_else(|| invalid_argument("a percentage"));
+        let no_argument = |filter: Filter| match argument.is_empty() {
+            true => Ok(filter),
+            false => Err(invalid_argument("no argument")),
+        };
+
+        match name {
+            FILTER_LIGHTEN => percentage().map(Filter::Lighten),
+            FILTER_DARKEN => percentage().map(Filter::Darken),
+            FILTER_SATURATE => percentage().map(Filter::Saturate),
+            FILTER_DESATURATE => percentage().map(Filter::Desaturate),
+            FILTER_HUE_ROTATE => number(&argument, "deg")
+                .map(Filter::HueRotate)
+                .ok_or_else(|| invalid_argument("degrees")),
+            FILTER_INVERT => no_argument(Filter::Invert),
+            FILTER_GRAYSCALE => no_argument(Filter::Grayscale),
+            FILTER_CONTRAST_FG => no_argument(Filter::ContrastFg),
+            // Either a percentage or a number in [0, 1]
+            FILTER_ALPHA => {
+                let opacity = match argument.ends_with('%') {
+                    true => number(&argument, "%").map(|opacity| opacity / 100.0),
+                    false => argument.parse::<f32>().ok(),
+                };
+                opacity
+                    .filter(|opacity| (0.0..=1.0).contains(opacity))
+                    .map(Filter::Alpha)
+                    .ok_or_else(|| invalid_argument("a number in [0, 1] or a percentage"))
+            }
+            FILTER_MIX => MixSpace::from_name(&argument).map(Filter::Mix),
+            FILTER_FORMAT => ColorFormat::parse_preset(&argument)
+                .map(Filter::Format)
+                .ok_or_else(|| {
+                    invalid_argument(&format!(
+                        "one of the color format presets `{}`",
+                        COLOR_FORMAT_PRESETS.map(|(name, _)| name).join("`, `")
+                    ))
+                }),
+            _ => Err(format!(
+                "Unknown filter `{}`. Valid filters are `{}`",
+                name,
+                Filter::NAMES.join("`, `")
+            )),
+        }
+    }
+
+    // Applies the filter to a color. The foreground is used by
+    // `contrast_fg`. Mix spaces and formats don't change the color
+    pub fn apply(&self, color: &RGBA, foreground: Option<&RGBA>) -> RGBA {
+        match self {
+            Filter::Lighten(amount) => color.map_rgb(|c| c.lighten(*amount)),
+            Filter::Darken(amount) => color.map_rgb(|c| c.lighten(-amount)),
+            Filter::Saturate(amount) => color.map_rgb(|c| c.saturate(*amount)),
+            Filter::Desaturate(amount) => color.map_rgb(|c| c.saturate(-amount)),
+            Filter::HueRotate(degrees) => color.map_rgb(|c| c.hue_rotate(*degrees)),
+            Filter::Invert => color.map_rgb(|c| RGB(255 - c.0, 255 - c.1, 255 - c.2)),
+            // Gray with the same luminance
+            Filter::Grayscale => color.map_rgb(|c| {
+                let gray = (c.luminance() * 255.0).round() as u8;
+                RGB(gray, gray, gray)
+            }),
+            Filter::ContrastFg => {
+                let background = &color.rgb;
+                match foreground {
+                    Some(foreground)
+                        if foreground.contrast_ratio(background) >= CONTRAST_FG_RATIO =>
+                    {
+                        RGBA::from(foreground.rgb.clone())
+                    }
+                    _ => {
+                        let (black, white) = (RGB(0, 0, 0), RGB(255, 255, 255));
+                        match black.contrast_ratio(background) >= white.contrast_ratio(background) {
+                            true => black.into(),
+                            false => white.into(),
+                        }
+                    }
+                }
+            }
+            Filter::Alpha(opacity) => RGBA {
+                rgb: color.rgb.clone(),
+                alpha: (opacity * 255.0).round() as u8,
+            },
+            Filter::Mix(_) | Filter::Format(_) => color.clone(),
+        }
+    }
+}
+
+// Number with an optional unit, e.g. `10%` or `-30deg`
+fn number(source: &str, unit: &str) -> Option<f32> {
+    source.strip_suffix(unit).unwrap_or(source).parse().ok()
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(filter: &str, color: RGB) -> RGB {
+        Filter::parse(filter)
+            .unwrap()
+            .apply(&color.into(), Some(&RGB(200, 200, 200).into()))
+            .rgb
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Filter::parse("lighten 10%"), Ok(Filter::Lighten(10.0)));
+        assert_eq!(Filter::parse(" darken   5 "), Ok(Filter::Darken(5.0)));
+        assert_eq!(
+            Filter::parse("hue-rotate -30deg"),
+            Ok(Filter::HueRotate(-30.0))
+        );
+        assert_eq!(Filter::parse("alpha 0.8"), Ok(Filter::Alpha(0.8)));
+        assert_eq!(Filter::parse("alpha 80%"), Ok(Filter::Alpha(0.8)));
+        assert_eq!(Filter::parse("alpha:80"), Ok(Filter::Alpha(0.8)));
+        assert_eq!(Filter::parse("mix:oklab"), Ok(Filter::Mix(MixSpace::Oklab)));
+        assert_eq!(
+            Filter::parse("fmt rgb"),
+            Ok(Filter::Format(ColorFormat::parse("rgb").unwrap()))
+        );
+        assert_eq!(Filter::parse("invert"), Ok(Filter::Invert));
+
+        assert!(Filter::parse("lightn 10%")
+            .unwrap_err()
+            .starts_with("Unknown filter `lightn`"));
+        assert_eq!(
+            Filter::parse("lighten"),
+            Err("Missing argument of filter `lighten`, expected a percentage".to_string())
+        );
+        for filter in [
+            "alpha 80",
+            "invert 10%",
+            "fmt hex",
+            "fmt rgb({r}, {g}, {b})",
+            "mix cmyk",
+            "saturate 1px",
+        ] {
+            assert!(Filter::parse(filter).is_err(), "{}", filter);
+        }
+    }
+
+    #[test]
+    fn colors() {
+        let red = RGB(255, 0, 0);
+        assert_eq!(apply("lighten 50%", red.clone()), RGB(255, 255, 255));
+        assert_eq!(apply("darken 50%", red.clone()), RGB(0, 0, 0));
+        assert_eq!(apply("desaturate 100%", red.clone()), RGB(128, 128, 128));
+        assert_eq!(apply("hue-rotate 120deg", red.clone()), RGB(0, 255, 0));
+        assert_eq!(apply("invert", red.clone()), RGB(0, 255, 255));
+        assert_eq!(apply("grayscale", red), RGB(54, 54, 54));
+
+        let color = Filter::Alpha(0.8).apply(&RGB(16, 32, 48).into(), None);
+        assert_eq!(color.alpha, 204);
+    }
+
+    #[test]
+    fn contrast_fg() {
+        // The foreground is readable on dark backgrounds only
+        assert_eq!(apply("contrast_fg", RGB(20, 20, 30)), RGB(200, 200, 200));
+        assert_eq!(apply("contrast_fg", RGB(240, 240, 220)), RGB(0, 0, 0));
+        assert_eq!(apply("contrast_fg", RGB(120, 120, 120)), RGB(0, 0, 0));
+
+        let color = Filter::ContrastFg.apply(&RGB(0, 0, 128).into(), None);
+        assert_eq!(color.rgb, RGB(255, 255, 255));
+    }
+}
@@ -1,22 +1,36 @@
+mod filter;
 mod parse_color;
 mod parse_directive;
 
 use crate::colortable::ColorTable;
 use crate::config::blueprint::directive;
-use crate::config::environ::{CACHE_BLUEPRINTS_DIR, CONFIG_BLUEPRINTS_DIR, POST_EXEC_SCRIPT};
+use crate::config::environ::{
+    BLUEPRINTS_DIRS, CACHE_BLUEPRINTS_DIR, CONFIG_BLUEPRINTS_DIR, POST_EXEC_SCRIPT,
+};
 use crate::logging::{
     log_as_error, Error, Error::BlueprintError, Error::ExecError, Error::SystemError,
 };
 use parse_color::parse_color;
 use parse_directive::Directive;
+use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs::{read_dir, DirEntry, File};
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::Command;
 
 pub fn build_blueprints(colors: &mut ColorTable) -> Result<(), Error> {
-    // Looks for blueprints in both cache and config directories
-    for dir in &[&*CONFIG_BLUEPRINTS_DIR, &*CACHE_BLUEPRINTS_DIR] {
+    // Blueprints already built, by file name. A blueprint shadows the
+    // ones with the same name in the following directories
+    let mut built: HashSet<OsString> = HashSet::new();
+
+    // Looks for blueprints in all the search paths, by decreasing priority
+    for dir in BLUEPRINTS_DIRS.iter() {
+        // The shared and system directories may not exist
+        if !dir.exists() && *dir != *CONFIG_BLUEPRINTS_DIR && *dir != *CACHE_BLUEPRINTS_DIR {
+            continue;
+        }
+
         // Reads the content of the directory
         match read_dir(dir) {
             Err(e) => log_as_error(SystemError(e.to_string())),
@@ -26,6 +40,10 @@ pub fn build_blueprints(colors: &mut ColorTable) -> Result<(), Error> {
                     match blueprint {
                         Err(e) => log_as_error(SystemError(e.to_string())),
                         Ok(blueprint) => {
+                            if !built.insert(blueprint.file_name()) {
+                                continue;
+                            }
+
                             // Checks if blueprint is a file and runs it
                             let result = build_blueprint_form_dir_entry(&blueprint, colors);
                             if let Err(BlueprintError(e)) = result {
@@ -66,7 +84,7 @@ pub fn build_blueprint(path: &PathBuf, colors: &mut ColorTable) -> Result<(), Er
 
     // Parses directives and colors
     let mut parsing_directive = true;
-    for line in reader.lines() {
+    for (index, line) in reader.lines().enumerate() {
         let line = line.map_err(|e| BlueprintError(e.to_string()))?;
 
         // Parses directives only at the very beginning of the file
@@ -77,7 +95,7 @@ pub fn build_blueprint(path: &PathBuf, colors: &mut ColorTable) -> Result<(), Er
         if parsing_directive {
             directives.parse(&line).map_err(|e| BlueprintError(e))?;
         } else {
-            blueprint_instance.push_str(&parse_color(&line, colors, &directives, &path));
+            blueprint_instance.push_str(&parse_color(&line, index + 1, colors, &directives, &path));
             blueprint_instance.push_str("\n");
         }
     }
@@ -1,23 +1,28 @@
+use super::filter::Filter;
 use super::parse_directive::Directive;
-use crate::colortable::rgb::RGB;
+use crate::colortable::rgba::RGBA;
 use crate::colortable::ColorTable;
-use crate::config::blueprint::MIXED_COLOR_FIELD_SEPARATOR;
+use crate::config::blueprint::{FILTER_SEPARATOR, MIXED_COLOR_FIELD_SEPARATOR};
+use crate::config::colorscheme::FOREGROUND;
 use crate::logging::{log_as_warning, Error::BlueprintError};
 use const_format::formatcp;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-// Assuming MIXED_COLOR_FIELD_SEPARATOR = ":", the regex becomes r"\{(\w+)(:(\d+):(\w+))?\}"
+// Assuming MIXED_COLOR_FIELD_SEPARATOR = ":" and FILTER_SEPARATOR = "|",
+// the regex becomes r"\{(\w+)(:(\d+):(\w+))?((\s*\|[^{}|]*)*)\}"
+// Filters are parsed on their own, so that errors can point to them
 static COLOR_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(formatcp!(
-        r"\{{(\w+)({MIXED_COLOR_FIELD_SEPARATOR}(\d+){MIXED_COLOR_FIELD_SEPARATOR}(\w+))?\}}"
+        r"\{{(\w+)({MIXED_COLOR_FIELD_SEPARATOR}(\d+){MIXED_COLOR_FIELD_SEPARATOR}(\w+))?((\s*\{FILTER_SEPARATOR}[^{{}}{FILTER_SEPARATOR}]*)*)\}}"
     ))
     .unwrap()
 });
 
 pub fn parse_color(
     line: &str,
+    line_number: usize,
     colors: &mut ColorTable,
     directives: &Directive,
     blueprint: &PathBuf,
@@ -28,48 +33,332 @@ pub fn parse_color(
             let color1 = caps.get(1);
             let amount = caps.get(3);
             let color2 = caps.get(4);
+            let filters = caps.get(5).unwrap();
+
+            // The whole matching color expression (minus the starting/closing
+            // curly braces). Used only when raising errors
+            let whole_match = caps.get(0).unwrap().as_str();
+            let whole_color = &whole_match[1..whole_match.len() - 1];
+
+            let filters = match parse_filters(filters.start(), filters.as_str()) {
+                Ok(filters) => filters,
+                Err((offset, e)) => {
+                    // Text with an unknown filter may not be a placeholder
+                    // at all (e.g. the syntax of another tool), so it's
+                    // left as it is
+                    let unknown = filters
+                        .as_str()
+                        .split(FILTER_SEPARATOR)
+                        .skip(1)
+                        .any(|source| !Filter::NAMES.contains(&Filter::name(source)));
+                    let (replacement, outcome) = match unknown {
+                        true => (whole_match, "Leaving it as it is"),
+                        false => ("", "Can't replace it in the blueprint"),
+                    };
+
+                    log_as_warning(BlueprintError(format!(
+                        "{}. {} in color `{}`. {}.",
+                        location(blueprint, line_number, line, offset),
+                        e,
+                        whole_color,
+                        outcome,
+                    )));
+                    return replacement.to_string();
+                }
+            };
+
+            // The mix space and the color format can be overridden by
+            // the filters
+            let mut mix_space = directives.mix_space;
+            let mut color_format = &directives.color_format;
+            for filter in &filters {
+                match filter {
+                    Filter::Mix(space) => mix_space = *space,
+                    Filter::Format(format) => color_format = format,
+                    _ => (),
+                }
+            }
 
             // The first color is always present
-            let color1 = color1.unwrap().as_str().to_string();
+            let color1_match = color1.unwrap();
+            let color1 = color1_match.as_str().to_string();
 
-            let color: Option<&RGB> = if color2.is_some() {
+            let color: Option<&RGBA> = if color2.is_some() {
                 // Returns the mixed color
                 let color2: String = color2.unwrap().as_str().to_string();
-                let amount: u8 = amount.unwrap().as_str().parse().unwrap();
-                colors.get_composite(&color1, amount, &color2)
+
+                // The amount is the percentage of the first color
+                let amount = amount.unwrap();
+                let amount: u8 = match amount.as_str().parse() {
+                    Ok(percentage) if percentage <= 100 => percentage,
+                    _ => {
+                        log_as_warning(BlueprintError(format!(
+                            "{}. Invalid amount `{}` in color `{}`, it must be between 0 and 100. Leaving it as it is.",
+                            location(blueprint, line_number, line, amount.start()),
+                            amount.as_str(),
+                            whole_color,
+                        )));
+                        return whole_match.to_string();
+                    }
+                };
+                colors.get_composite(&color1, amount, &color2, mix_space)
             } else {
                 // Returns just the first color
                 colors.get(&color1.to_string())
             };
 
-            // The whole matching color expression (minus the starting/closing
-            // curly braces). Used only when raising the last two errors
-            let whole_match = caps.get(0).unwrap().as_str();
-            let whole_color = &whole_match[1..whole_match.len() - 1];
+            // Applies the filters from left to right
+            let color: Option<RGBA> = color.cloned().map(|color| {
+                let foreground = colors.get(FOREGROUND);
+                filters
+                    .iter()
+                    .fold(color, |color, filter| filter.apply(&color, foreground))
+            });
 
             // Formats and returns the color
-            if let Some(color) = color {
-                let formatted_color = color.format(&directives.color_format);
-
-                if formatted_color.is_ok() {
-                    return formatted_color.unwrap();
-                } else {
+            match color {
+                Some(color) => color_format.render(&color.rgb, color.alpha),
+                None => {
+                    // Points to the first color missing from the table
+                    let missing = match colors.get(&color1) {
+                        None => color1_match,
+                        Some(_) => color2.unwrap(),
+                    };
                     log_as_warning(BlueprintError(format!(
-                        "While parsing blueprint `{}`. An error occurred while formatting color `{}` as `{}`. Can't replace it in the blueprint.",
-                        blueprint.display(),
+                        "{}. Unknown color `{}` in color `{}`. Can't replace it in the blueprint.",
+                        location(blueprint, line_number, line, missing.start()),
+                        missing.as_str(),
                         whole_color,
-                        &directives.color_format
                     )));
+                    "".to_string()
                 }
-            } else {
-                log_as_warning(BlueprintError(format!(
-                    "While parsing blueprint `{}`. An error occurred while retrieving color `{}`. Can't replace it in the blueprint.",
-                    blueprint.display(),
-                    whole_color,
-                )));
             }
-
-            "".to_string()
         })
         .to_string()
 }
+
+// Location of byte `offset` of a line, which starts the warnings about
+// the placeholders. Columns count characters, from 1
+fn location(blueprint: &Path, line_number: usize, line: &str, offset: usize) -> String {
+    format!(
+        "While parsing blueprint `{}`, line {}, column {}",
+        blueprint.display(),
+        line_number,
+        line[..offset].chars().count() + 1,
+    )
+}
+
+// Parses the filters of a placeholder, e.g. ` | lighten 10% | fmt rgb`,
+// found at byte `start` of the line. Errors come with the byte offset of
+// the faulty filter in the line
+fn parse_filters(start: usize, filters: &str) -> Result<Vec<Filter>, (usize, String)> {
+    let mut parsed = Vec::new();
+    let mut offset = start;
+
+    // The first field is the space before the first separator
+    for (index, source) in filters.split(FILTER_SEPARATOR).enumerate() {
+        if index > 0 {
+            let name_offset = offset + source.len() - source.trim_start().len();
+            parsed.push(Filter::parse(source).map_err(|e| (name_offset, e))?);
+        }
+        offset += source.len() + FILTER_SEPARATOR.len();
+    }
+
+    Ok(parsed)
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::color_format::ColorFormat;
+    use crate::colortable::rgb::{MixSpace, RGB};
+    use crate::config::blueprint::directive::{
+        HEX_6_DIGITS_W_HASHTAG, HEX_8_DIGITS_W_HASHTAG, RGBA_FUNCTION, RGB_FUNCTION,
+    };
+
+    fn parse(line: &str, color_format: &str) -> String {
+        let mut colors = ColorTable::with_capacity(3);
+        colors.insert("background".to_string(), RGB(16, 32, 48).into());
+        colors.insert("foreground".to_string(), RGB(255, 255, 255).into());
+        colors.insert("black".to_string(), RGB(0, 0, 0).into());
+
+        let mut directives = Directive::new();
+        directives.color_format = ColorFormat::parse(color_format).unwrap();
+
+        parse_color(line, 1, &mut colors, &directives, &PathBuf::from("test"))
+    }
+
+    #[test]
+    fn alpha_modifier() {
+        assert_eq!(
+            parse("bg = {background|alpha:80}", HEX_8_DIGITS_W_HASHTAG),
+            "bg = #102030CC"
+        );
+        assert_eq!(
+            parse("bg = {background}", HEX_8_DIGITS_W_HASHTAG),
+            "bg = #102030FF"
+        );
+        assert_eq!(
+            parse("{background:50:foreground|alpha:0}", RGBA_FUNCTION),
+            "rgba(136, 144, 152, 0)"
+        );
+
+        // Invalid opacities are reported, instead of being ignored
+        let (_, e) = parse_filters(11, "|alpha:8O").unwrap_err();
+        assert!(
+            e.starts_with("Invalid argument `8O` of filter `alpha`"),
+            "{}",
+            e
+        );
+        assert_eq!(parse("bg = {background|alpha:8O}", RGBA_FUNCTION), "bg = ");
+    }
+
+    #[test]
+    fn mix_modifier() {
+        // 50% white over black, in every mix space
+        assert_eq!(
+            parse("{foreground:50:black}", HEX_6_DIGITS_W_HASHTAG),
+            "#808080"
+        );
+        assert_eq!(
+            parse(
+                "{foreground:50:black|mix:linear-srgb}",
+                HEX_6_DIGITS_W_HASHTAG
+            ),
+            "#BCBCBC"
+        );
+        assert_eq!(
+            parse("{foreground:50:black|mix:oklab}", HEX_6_DIGITS_W_HASHTAG),
+            "#636363"
+        );
+        assert_eq!(
+            parse(
+                "{foreground:50:black|alpha:0|mix:hsl}",
+                HEX_8_DIGITS_W_HASHTAG
+            ),
+            "#80808000"
+        );
+
+        // The modifier wins over the directive
+        let mut colors = ColorTable::with_capacity(2);
+        colors.insert("foreground".to_string(), RGB(255, 255, 255).into());
+        colors.insert("black".to_string(), RGB(0, 0, 0).into());
+        let mut directives = Directive::new();
+        directives.mix_space = MixSpace::LinearSrgb;
+        let blueprint = PathBuf::from("test");
+        let line = "{foreground:50:black} {foreground:50:black|mix:srgb}";
+        assert_eq!(
+            parse_color(line, 1, &mut colors, &directives, &blueprint),
+            "#BCBCBC #808080"
+        );
+
+        // Each mix space is cached separately
+        assert!(colors.contains_key("foreground:50:black:linear-srgb"));
+        assert!(colors.contains_key("foreground:50:black:srgb"));
+
+        // The amount is the share of the first color, from 0 to 100, in
+        // every mix space
+        for space in MixSpace::NAMES {
+            let line = format!(
+                "{{background:0:foreground|mix:{space}}} {{background:100:foreground|mix:{space}}}"
+            );
+            assert_eq!(parse(&line, HEX_6_DIGITS_W_HASHTAG), "#FFFFFF #102030");
+        }
+        let line = "a {background:150:foreground} b";
+        assert_eq!(parse(line, HEX_6_DIGITS_W_HASHTAG), line);
+
+        // Invalid mix spaces aren't replaced
+        assert_eq!(
+            parse("{foreground:50:black|mix:cmyk}", HEX_6_DIGITS_W_HASHTAG),
+            ""
+        );
+    }
+
+    #[test]
+    fn filters() {
+        assert_eq!(
+            parse(
+                "{background | lighten 10% | alpha 0.8 | fmt rgba}",
+                HEX_6_DIGITS_W_HASHTAG
+            ),
+            "rgba(29, 58, 86, 0.8)"
+        );
+        assert_eq!(
+            parse("{background|invert} {black | grayscale}", RGB_FUNCTION),
+            "rgb(239, 223, 207) rgb(0, 0, 0)"
+        );
+
+        // Readable text colors on top of the background and the foreground
+        assert_eq!(
+            parse(
+                "{background|contrast_fg} {foreground|contrast_fg}",
+                HEX_6_DIGITS_W_HASHTAG
+            ),
+            "#FFFFFF #000000"
+        );
+
+        // Filters apply to the composite colors too
+        assert_eq!(
+            parse(
+                "{foreground:50:black | mix oklab | darken 10}",
+                HEX_6_DIGITS_W_HASHTAG
+            ),
+            "#4A4A4A"
+        );
+
+        // Braces that aren't placeholders are left untouched
+        assert_eq!(
+            parse("a { b | c } {background}", HEX_6_DIGITS_W_HASHTAG),
+            "a { b | c } #102030"
+        );
+    }
+
+    #[test]
+    fn filter_errors() {
+        let line = "fg = {foreground | lighten 5% | lightn 10%}";
+        let (start, filters) = (16, " | lighten 5% | lightn 10%");
+        assert_eq!(&line[start..start + filters.len()], filters);
+
+        let (offset, e) = parse_filters(start, filters).unwrap_err();
+        assert_eq!(
+            location(Path::new("test"), 1, line, offset),
+            "While parsing blueprint `test`, line 1, column 33"
+        );
+        assert!(e.starts_with("Unknown filter `lightn`"));
+        // Unknown filters leave the text untouched
+        assert_eq!(parse(line, HEX_6_DIGITS_W_HASHTAG), line);
+        assert_eq!(
+            parse("{a|b} {foreground}", RGB_FUNCTION),
+            "{a|b} rgb(255, 255, 255)"
+        );
+
+        // Columns count characters, not bytes
+        let (offset, _) = parse_filters(3, "|alpha 2").unwrap_err();
+        assert_eq!(
+            location(Path::new("test"), 2, "{é|alpha 2}", offset),
+            "While parsing blueprint `test`, line 2, column 4"
+        );
+    }
+
+    #[test]
+    fn color_errors() {
+        // Out of range amounts leave the text untouched, like unknown
+        // filters
+        let line = "selection = {background:300:foreground}";
+        assert_eq!(parse(line, HEX_6_DIGITS_W_HASHTAG), line);
+        assert_eq!(
+            parse(
+                "{background:100:foreground} {background:101:foreground}",
+                RGB_FUNCTION
+            ),
+            "rgb(16, 32, 48) {background:101:foreground}"
+        );
+
+        // Unknown colors can't be replaced
+        assert_eq!(
+            parse("a = {color_04} b = {background:50:color_04}", RGB_FUNCTION),
+            "a =  b = "
+        );
+    }
+}
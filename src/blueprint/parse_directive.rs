@@ -1,5 +1,7 @@
+use crate::colortable::color_format::ColorFormat;
+use crate::colortable::rgb::MixSpace;
 use crate::config::blueprint::directive::{
-    DIRECTIVE_COLOR_FORMAT, DIRECTIVE_OUTPUT_DIRECTORY, HEX_6_DIGITS_WO_HASHTAG,
+    DIRECTIVE_COLOR_FORMAT, DIRECTIVE_MIX_SPACE, DIRECTIVE_OUTPUT_DIRECTORY,
     HEX_6_DIGITS_W_HASHTAG, PREFIX, SEPARATOR,
 };
 use crate::config::environ::OUT_DIR;
@@ -13,13 +15,19 @@ use std::path::PathBuf;
 static REGEX_KEY_VAL_DIRECTIVE: Lazy<Regex> =
     Lazy::new(|| Regex::new(formatcp!(r"^{PREFIX}((\w|-)+){SEPARATOR}\s*(.+)\s*$")).unwrap());
 
-const COLOR_FORMATS: [&str; 2] = [HEX_6_DIGITS_WO_HASHTAG, HEX_6_DIGITS_W_HASHTAG];
-const DIRECTIVE_TYPES: [&str; 2] = [DIRECTIVE_COLOR_FORMAT, DIRECTIVE_OUTPUT_DIRECTORY];
+const DIRECTIVE_TYPES: [&str; 3] = [
+    DIRECTIVE_COLOR_FORMAT,
+    DIRECTIVE_OUTPUT_DIRECTORY,
+    DIRECTIVE_MIX_SPACE,
+];
 
 #[derive(Debug)]
 pub struct Directive {
-    pub color_format: String,
+    // Parsed once, then used by every placeholder of the blueprint
+    pub color_format: ColorFormat,
     pub output_directory: PathBuf,
+    // Color space of the composite colors, e.g. `{background:80:color_04}`
+    pub mix_space: MixSpace,
 }
 
 impl Directive {
@@ -27,8 +35,9 @@ impl Directive {
     // the parent of the blueprint being parsed
     pub fn new() -> Self {
         Self {
-            color_format: HEX_6_DIGITS_W_HASHTAG.to_string(),
+            color_format: ColorFormat::parse(HEX_6_DIGITS_W_HASHTAG).unwrap(),
             output_directory: OUT_DIR.to_path_buf(),
+            mix_space: MixSpace::default(),
         }
     }
 
@@ -49,6 +58,9 @@ impl Directive {
             // Output directory directive
             DIRECTIVE_OUTPUT_DIRECTORY => self.update_output_directory(directive_value)?,
 
+            // Mix space directive
+            DIRECTIVE_MIX_SPACE => self.mix_space = MixSpace::from_name(directive_value)?,
+
             // Invalid directive
             _ => {
                 return Err(format!(
@@ -63,17 +75,8 @@ impl Directive {
     }
 
     fn update_color_format(&mut self, color_format: &str) -> Result<(), String> {
-        // Checks if the color format exists
-        if !COLOR_FORMATS.contains(&color_format) {
-            return Err(format!(
-                "Invalid color format `{}`. Valid color formats are `{}`.",
-                color_format,
-                COLOR_FORMATS.join("`, `")
-            ));
-        }
-
-        // Updates self
-        self.color_format = color_format.to_string();
+        // Checks if the color format is a preset or a valid template
+        self.color_format = ColorFormat::parse(color_format)?;
 
         Ok(())
     }
@@ -110,21 +113,24 @@ impl Directive {
 mod tests {
     use super::*;
     use crate::config::blueprint::directive::{
-        DIRECTIVE_COLOR_FORMAT, DIRECTIVE_OUTPUT_DIRECTORY, HEX_6_DIGITS_W_HASHTAG, PREFIX,
-        SEPARATOR,
+        COLOR_FORMAT_PRESETS, DIRECTIVE_COLOR_FORMAT, DIRECTIVE_MIX_SPACE,
+        DIRECTIVE_OUTPUT_DIRECTORY, HEX_6_DIGITS_W_HASHTAG, PREFIX, SEPARATOR,
     };
 
     fn empty_directive() -> Directive {
         Directive {
-            color_format: HEX_6_DIGITS_W_HASHTAG.to_string(),
+            color_format: ColorFormat::parse(HEX_6_DIGITS_W_HASHTAG).unwrap(),
             output_directory: PathBuf::from("/tmp"),
+            mix_space: MixSpace::default(),
         }
     }
 
     #[test]
     fn color_format_directive() {
         let mut d = empty_directive();
-        for color_format in COLOR_FORMATS {
+        let templates = ["rgb({r}, {g}, {b})", "{{ {r:f} {g:f} {b:f} }}"];
+        let presets = COLOR_FORMAT_PRESETS.map(|(name, _)| name);
+        for color_format in presets.iter().chain(&templates) {
             let statement =
                 format!("{PREFIX}{DIRECTIVE_COLOR_FORMAT}{SEPARATOR}{color_format}").to_owned();
             let result = d.parse(&statement);
@@ -134,11 +140,16 @@ mod tests {
                 statement
             );
             assert!(
-                d.color_format == color_format,
+                d.color_format == ColorFormat::parse(color_format).unwrap(),
                 "Directive `{}`. Color format did not update correctly",
                 statement
             );
         }
+
+        for color_format in ["hex", "rgb({r}, {green}, {b})"] {
+            let statement = format!("{PREFIX}{DIRECTIVE_COLOR_FORMAT}{SEPARATOR}{color_format}");
+            assert!(d.parse(&statement).is_err());
+        }
     }
 
     #[test]
@@ -159,4 +170,21 @@ mod tests {
             statement
         );
     }
+
+    #[test]
+    fn mix_space_directive() {
+        let mut d = empty_directive();
+        for mix_space in MixSpace::NAMES {
+            let statement = format!("{PREFIX}{DIRECTIVE_MIX_SPACE}{SEPARATOR}{mix_space}");
+            assert!(
+                d.parse(&statement).is_ok(),
+                "Directive `{}` failed to pass the test",
+                statement
+            );
+            assert_eq!(d.mix_space.name(), mix_space);
+        }
+
+        let statement = format!("{PREFIX}{DIRECTIVE_MIX_SPACE}{SEPARATOR}cmyk");
+        assert!(d.parse(&statement).is_err());
+    }
 }
@@ -1,5 +1,8 @@
+use crate::colortable::export::Target;
+use crate::colortable::generate::seed::Harmony;
+use crate::colortable::import::Source;
 use crate::config;
-use clap::{builder::PossibleValue, Arg, ArgAction, ArgGroup, Command};
+use clap::{builder::PossibleValue, value_parser, Arg, ArgAction, ArgGroup, Command};
 use const_format::formatcp;
 
 pub fn build_parser() -> Command {
@@ -23,6 +26,13 @@ pub fn build_parser() -> Command {
                         ))
                         .required(true),
                 )
+                // Variant option
+                .arg(
+                    Arg::new("variant")
+                        .long("variant")
+                        .help("Variant of the colorscheme to load (e.g. `light`). If not specified, the first one is used")
+                        .required(false),
+                )
         )
 
         /////////////////////
@@ -40,6 +50,220 @@ pub fn build_parser() -> Command {
                         ))
                         .required(true),
                 )
+                // Variant option
+                .arg(
+                    Arg::new("variant")
+                        .long("variant")
+                        .help("Variant of the colorscheme to preview (e.g. `light`). If not specified, the first one is used")
+                        .required(false),
+                )
+        )
+
+        ///////////////////////
+        // Import subcommand //
+        ///////////////////////
+        .subcommand(
+            Command::new("import")
+                .about(formatcp!(
+                    "Import a colorscheme from another format into `{}`",
+                    config::info::APP_NAME
+                ))
+                // File positional argument
+                .arg(
+                    Arg::new("file")
+                        .help("Path of the file to import. Use `-` to read from stdin (e.g. `xrdb -query`)")
+                        .required(true),
+                )
+                // Source format option
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .help("Format of the file to import. If not specified, it's detected from the file name and content")
+                        .required(false)
+                        .value_parser(value_parser!(Source))
+                )
+                // Name option
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .short('n')
+                        .help("Name of the new colorscheme. Defaults to the name declared in the file. Not allowed when the file holds several colorschemes")
+                        .required(false),
+                )
+                // Force option
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .short('f')
+                        .help("Overwrite an existing colorscheme with the same name")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+        )
+
+        /////////////////////////
+        // Generate subcommand //
+        /////////////////////////
+        .subcommand(
+            Command::new("generate")
+                .about("Generate a colorscheme out of the dominant colors of an image, or around a seed color")
+                // Image option
+                .arg(
+                    Arg::new("from-image")
+                        .long("from-image")
+                        .short('i')
+                        .help("PNG or JPEG image (e.g. a wallpaper) to extract the colors from")
+                        .required(false),
+                )
+                // Seed option
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .short('s')
+                        .help("HEX color (e.g. `#4a90e2`) to build the colorscheme around")
+                        .required(false),
+                )
+                .group(
+                    ArgGroup::new("source-group")
+                        .args(&["from-image", "seed"])
+                        .required(true),
+                )
+                // Harmony option
+                .arg(
+                    Arg::new("harmony")
+                        .long("harmony")
+                        .help("Which ANSI colors keep the chroma of the seed, the others are muted. Defaults to `analogous`")
+                        .required(false)
+                        .conflicts_with("from-image")
+                        .value_parser(value_parser!(Harmony))
+                )
+                // Dark/light options
+                .arg(
+                    Arg::new("dark")
+                        .long("dark")
+                        .short('d')
+                        .help("Generate a dark colorscheme (default)")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+                .arg(
+                    Arg::new("light")
+                        .long("light")
+                        .short('l')
+                        .help("Generate a light colorscheme instead of a dark one")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+                .group(ArgGroup::new("light-dark-group").args(&["dark", "light"]))
+                // Name option
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .short('n')
+                        .help("Name of the new colorscheme. Defaults to the name of the image, or to the seed and the harmony")
+                        .required(false),
+                )
+                // Force option
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .short('f')
+                        .help("Overwrite an existing colorscheme with the same name")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+        )
+
+        ///////////////////////
+        // Export subcommand //
+        ///////////////////////
+        .subcommand(
+            Command::new("export")
+                .about("Convert a colorscheme into the config format of a terminal application")
+                // Colorscheme positional argument
+                .arg(
+                    Arg::new("colorscheme")
+                        .help(formatcp!(
+                            "Name of the colorscheme to export. Run `{} list` to list the available themes",
+                            config::info::APP_NAME
+                        ))
+                        .required(true),
+                )
+                // Target format option
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .short('t')
+                        .help("Format to export the colorscheme to")
+                        .required(true)
+                        .value_parser(value_parser!(Target))
+                )
+                // Variant option
+                .arg(
+                    Arg::new("variant")
+                        .long("variant")
+                        .help("Variant of the colorscheme to export (e.g. `light`). If not specified, the first one is used")
+                        .required(false),
+                )
+                // Output option
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("File to write the exported colorscheme to. If not specified, it's printed to stdout")
+                        .required(false),
+                )
+        )
+
+        //////////////////////
+        // Check subcommand //
+        //////////////////////
+        .subcommand(
+            Command::new("check")
+                .about("Check colorschemes for errors, duplicate colors and unreadable colors")
+                // Colorschemes positional argument
+                .arg(
+                    Arg::new("colorscheme")
+                        .help("Names or paths of the colorschemes to check")
+                        .num_args(1..),
+                )
+                // All option
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .short('a')
+                        .help("Check all the colorschemes")
+                        .action(ArgAction::SetTrue),
+                )
+                .group(
+                    ArgGroup::new("check-target")
+                        .args(["colorscheme", "all"])
+                        .required(true),
+                )
+        )
+
+        ///////////////////////////////
+        // Toggle-variant subcommand //
+        ///////////////////////////////
+        .subcommand(
+            Command::new("toggle-variant")
+                .about("Switch the current colorscheme to its sibling variant (e.g. from `dark` to `light`)")
+        )
+
+        ///////////////////////
+        // Schema subcommand //
+        ///////////////////////
+        .subcommand(
+            Command::new("schema")
+                .about("Print the JSON Schema of the colorscheme format, for editor validation and completion")
+                // Output option
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("File to write the schema to. If not specified, it's printed to stdout")
+                        .required(false),
+                )
         )
 
         ///////////////////////
@@ -109,6 +333,58 @@ pub fn build_parser() -> Command {
                             PossibleValue::new("contrast")
                                 .aliases(["contr", "cont", "con", "cntr", "cnt"]),
                         ])
+                )
+                // Tag filter
+                .arg(
+                    Arg::new("tag")
+                        .long("tag")
+                        .short('t')
+                        .help("List only the colorschemes with the given tag. Can be repeated to require several tags")
+                        .action(ArgAction::Append)
+                        .required(false),
+                )
+                // Directory filter
+                .arg(
+                    Arg::new("dir")
+                        .long("dir")
+                        .help("List only the colorschemes inside a directory of the colorschemes directory, e.g. `catppuccin`")
+                        .required(false),
+                )
+                // Source column
+                .arg(
+                    Arg::new("source")
+                        .long("source")
+                        .short('s')
+                        .help("Show the directory each colorscheme comes from")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+                // Tree view
+                .arg(
+                    Arg::new("tree")
+                        .long("tree")
+                        .help("Show the colorschemes as a directory tree")
+                        .action(ArgAction::SetTrue)
+                        .required(false),
+                )
+                // Meta columns
+                .arg(
+                    Arg::new("meta")
+                        .long("meta")
+                        .short('m')
+                        .help("Comma separated metadata columns to show")
+                        .value_delimiter(',')
+                        .action(ArgAction::Append)
+                        .required(false)
+                        .value_parser([
+                            PossibleValue::new("name"),
+                            PossibleValue::new("author"),
+                            PossibleValue::new("variant"),
+                            PossibleValue::new("variants"),
+                            PossibleValue::new("description"),
+                            PossibleValue::new("tags"),
+                            PossibleValue::new("license"),
+                        ])
                 ),
         )
 
@@ -171,6 +447,7 @@ mod tests {
     #[test]
     fn load() {
         should_parse_auto_err("load my-theme");
+        should_parse_auto_err("load my-theme --variant light");
         should_fail_to_parse(
             "load",
             "Should fail cause positional argument `colorscheme` is missing, but got ok",
@@ -180,12 +457,95 @@ mod tests {
     #[test]
     fn preview() {
         should_parse_auto_err("preview my-theme");
+        should_parse_auto_err("preview my-theme --variant dark");
         should_fail_to_parse(
             "preview",
             "Should fail cause positional argument `colorscheme` is missing, but got ok",
         );
     }
 
+    #[test]
+    fn import() {
+        should_parse_auto_err("import scheme.yaml");
+        should_parse_auto_err("import scheme.yaml --name my-theme");
+        should_parse_auto_err("import scheme.yaml -n my-theme -f");
+        should_parse_auto_err("import scheme.yaml --force");
+        should_parse_auto_err("import scheme.itermcolors");
+        should_parse_auto_err("import - --name my-theme");
+        should_parse_auto_err("import theme.conf --from kitty");
+        should_parse_auto_err("import theme.toml --from alacritty");
+        should_parse_auto_err("import theme.ini --from foot");
+        should_parse_auto_err("import theme.toml --from wezterm");
+        should_parse_auto_err("import theme.yaml --from base24");
+        should_parse_auto_err("import - --from xrdb -n my-theme");
+        should_parse_auto_err("import settings.json --from windows-terminal");
+        should_parse_auto_err("import settings.json --from wt");
+        should_parse_auto_err("import settings.json --from vscode");
+        should_parse_auto_err("import themes.json --from gogh");
+        should_fail_to_parse(
+            "import theme.conf --from vim",
+            "Should fail to parse an unknown source format, but got ok",
+        );
+        should_fail_to_parse(
+            "import",
+            "Should fail cause positional argument `file` is missing, but got ok",
+        );
+    }
+
+    #[test]
+    fn generate() {
+        should_parse_auto_err("generate --from-image wallpaper.png");
+        should_parse_auto_err("generate -i wallpaper.jpg --light");
+        should_parse_auto_err("generate -i wallpaper.png -l -n my-theme -f");
+        should_fail_to_parse(
+            "generate",
+            "Should fail cause option `--from-image` is missing, but got ok",
+        );
+        should_fail_to_parse(
+            "generate wallpaper.png",
+            "Should fail cause the image is an option, but got ok",
+        );
+        should_parse_auto_err("generate --seed #4a90e2");
+        should_fail_to_parse(
+            "generate --from-image wallpaper.png --harmony triadic",
+            "Should fail cause `--harmony` only applies to `--seed`, but got ok",
+        );
+        should_parse_auto_err("generate -s 4a90e2 --harmony triadic --dark");
+        should_parse_auto_err("generate --seed #4a90e2 --harmony complementary -l -n blue");
+        should_fail_to_parse(
+            "generate --seed #4a90e2 --from-image wallpaper.png",
+            "Should fail cause `--seed` and `--from-image` conflict, but got ok",
+        );
+        should_fail_to_parse(
+            "generate --seed #4a90e2 --harmony square",
+            "Should fail to parse an unknown harmony, but got ok",
+        );
+        should_fail_to_parse(
+            "generate --seed #4a90e2 --dark --light",
+            "Should fail cause `--dark` and `--light` conflict, but got ok",
+        );
+    }
+
+    #[test]
+    fn export() {
+        should_parse_auto_err("export my-theme --to kitty");
+        should_parse_auto_err("export my-theme -t alacritty");
+        should_parse_auto_err("export my-theme --to foot -o foot.ini");
+        should_parse_auto_err("export my-theme --to wezterm --output my-theme.toml");
+        should_parse_auto_err("export my-theme --to xresources");
+        should_parse_auto_err("export my-theme --to iterm2");
+        should_parse_auto_err("export my-theme --to wt");
+        should_parse_auto_err("export my-theme --to kitty --variant light");
+        should_fail_to_parse(
+            "export my-theme",
+            "Should fail cause option `--to` is missing, but got ok",
+        );
+        should_fail_to_parse(
+            "export my-theme --to vim",
+            "Should fail to parse an unknown target format, but got ok",
+        );
+    }
+
     #[test]
     fn reload() {
         should_parse_auto_err("reload");
@@ -252,6 +612,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check() {
+        should_parse_auto_err("check my-theme");
+        should_parse_auto_err("check my-theme other-theme ./theme.toml");
+        should_parse_auto_err("check --all");
+        should_parse_auto_err("check -a");
+        should_fail_to_parse(
+            "check",
+            "Should fail cause neither a colorscheme nor `--all` is specified, but got ok",
+        );
+        should_fail_to_parse(
+            "check my-theme --all",
+            "Should fail cause both a colorscheme and `--all` are specified, but got ok",
+        );
+    }
+
+    #[test]
+    fn toggle_variant() {
+        should_parse_auto_err("toggle-variant");
+        should_fail_to_parse(
+            "toggle-variant light",
+            "Should fail cause `toggle-variant` takes no arguments, but got ok",
+        );
+    }
+
+    #[test]
+    fn schema() {
+        should_parse_auto_err("schema");
+        should_parse_auto_err("schema --output colorscheme.schema.json");
+        should_parse_auto_err("schema -o colorscheme.schema.json");
+    }
+
+    #[test]
+    fn list_tree() {
+        should_parse_auto_err("list --tree");
+        should_parse_auto_err("list --dir catppuccin");
+        should_parse_auto_err("list --tree --dir catppuccin --dark");
+        should_parse_auto_err("list --source");
+        should_parse_auto_err("list -s --meta author");
+    }
+
+    #[test]
+    fn list_meta() {
+        should_parse_auto_err("list --tag retro");
+        should_parse_auto_err("list -t retro -t warm --dark");
+        should_parse_auto_err("list --meta author,variant");
+        should_parse_auto_err("list -m tags -m license");
+
+        should_fail_to_parse(
+            "list --meta homepage",
+            "Should fail to parse an unknown meta column",
+        );
+    }
+
     #[test]
     fn verbosity_group() {
         should_parse_auto_err("-v list");
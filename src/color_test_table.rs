@@ -44,4 +44,21 @@ pub fn print_color_test_table(colors: &ColorTable) {
 
     // Empty line
     let _ = print_with_custom_colors(main_bg, main_fg, format!("{:<58}\n", ""));
+
+    // Prints the extra colors, one per row: the color name, the test
+    // string over the main background and a sample of the color
+    for name in colors.extra_color_names() {
+        let color = colors.get(name).unwrap();
+
+        let _ = print_with_custom_colors(main_bg, main_fg, format!(" {:<30} ", name));
+        let _ = print_with_custom_colors(main_bg, color, " gYw ".to_string());
+        let _ = print_with_custom_colors(main_bg, main_fg, " ".to_string());
+        let _ = print_with_custom_colors(color, color, format!("{:<19}", ""));
+        let _ = print_with_custom_colors(main_bg, main_fg, " \n".to_string());
+    }
+
+    // Empty line after the extra colors
+    if !colors.extra_color_names().is_empty() {
+        let _ = print_with_custom_colors(main_bg, main_fg, format!("{:<58}\n", ""));
+    }
 }
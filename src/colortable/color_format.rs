@@ -0,0 +1,300 @@
+// Color formats of the blueprints (see the `%color-format` directive).
+//
+// A color format is either the name of a preset (see
+// COLOR_FORMAT_PRESETS) or a template such as `rgb({r}, {g}, {b})`.
+// Fields are written between braces, optionally followed by a notation:
+//
+//   r, g, b, a   channels in [0, 255]
+//   h            hue in degrees [0, 360)
+//   s, l         saturation and lightness in percent [0, 100]
+//   int          24 bits integer (0xRRGGBB)
+//
+//   {r}          decimal
+//   {r:x} {r:X}  lowercase and uppercase hexadecimal, 2 digits (6 for int)
+//   {r:f}        float in [0, 1], at most 3 decimals
+//   {r:.2f}      float in [0, 1], exactly 2 decimals
+//
+// Braces are escaped by doubling them: `{{` and `}}`.
+
+use super::rgb::RGB;
+use crate::config::blueprint::directive::COLOR_FORMAT_PRESETS;
+
+const FIELDS: [&str; 8] = ["r", "g", "b", "a", "h", "s", "l", "int"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Field {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    Hue,
+    Saturation,
+    Lightness,
+    Integer,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Notation {
+    Decimal,
+    LowerHex,
+    UpperHex,
+    // Number of decimals, at most 3 without trailing zeros by default
+    Float(Option<usize>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Text(String),
+    Field(Field, Notation),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorFormat(Vec<Segment>);
+
+impl ColorFormat {
+    // Parses the name of a preset or a template. Anything without a field
+    // is taken for the name of a preset
+    pub fn parse(format: &str) -> Result<Self, String> {
+        if let Some(preset) = Self::parse_preset(format) {
+            return Ok(preset);
+        }
+
+        if !format.contains('{') {
+            return Err(format!(
+                "Invalid color format `{}`. Valid color formats are `{}`, or a template such as `rgb({{r}}, {{g}}, {{b}})`",
+                format,
+                COLOR_FORMAT_PRESETS
+                    .map(|(name, _)| name)
+                    .join("`, `")
+            ));
+        }
+
+        Self::parse_template(format)
+    }
+
+    // Parses the name of a preset only
+    pub fn parse_preset(name: &str) -> Option<Self> {
+        COLOR_FORMAT_PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, template)| Self::parse_template(template).unwrap())
+    }
+
+    fn parse_template(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => {
+                                return Err(format!("Unclosed `{{` in color format `{}`", template))
+                            }
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(parse_field(&field, template)?);
+                }
+                '}' => {
+                    return Err(format!(
+                        "Unmatched `}}` in color format `{}`. Use `}}}}` for a literal brace",
+                        template
+                    ))
+                }
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self(segments))
+    }
+
+    // Writes a color and its alpha channel with the format
+    pub fn render(&self, rgb: &RGB, alpha: u8) -> String {
+        let RGB(r, g, b) = *rgb;
+        let (hue, saturation, lightness) = rgb.to_hsl();
+        let mut output = String::new();
+
+        for segment in &self.0 {
+            let (field, notation) = match segment {
+                Segment::Text(text) => {
+                    output.push_str(text);
+                    continue;
+                }
+                Segment::Field(field, notation) => (field, notation),
+            };
+
+            // Value, its range, and the number of hexadecimal digits
+            let (value, max, width) = match field {
+                Field::Red => (r as f32, 255.0, 2),
+                Field::Green => (g as f32, 255.0, 2),
+                Field::Blue => (b as f32, 255.0, 2),
+                Field::Alpha => (alpha as f32, 255.0, 2),
+                Field::Hue => (hue.round() % 360.0, 360.0, 0),
+                Field::Saturation => (saturation * 100.0, 100.0, 0),
+                Field::Lightness => (lightness * 100.0, 100.0, 0),
+                Field::Integer => (
+                    (((r as u32) << 16) | ((g as u32) << 8) | b as u32) as f32,
+                    0xFFFFFF as f32,
+                    6,
+                ),
+            };
+
+            let formatted = match notation {
+                Notation::Decimal => format!("{}", value.round() as u32),
+                Notation::LowerHex => format!("{:0width$x}", value as u32, width = width),
+                Notation::UpperHex => format!("{:0width$X}", value as u32, width = width),
+                Notation::Float(None) => {
+                    format!("{}", (value / max * 1000.0).round() / 1000.0)
+                }
+                Notation::Float(Some(decimals)) => {
+                    format!("{:.decimals$}", value / max, decimals = decimals)
+                }
+            };
+            output.push_str(&formatted);
+        }
+
+        output
+    }
+}
+
+// Parses the content of a placeholder, e.g. `r:x`
+fn parse_field(field: &str, template: &str) -> Result<Segment, String> {
+    let (name, notation) = match field.split_once(':') {
+        Some((name, notation)) => (name.trim(), Some(notation.trim())),
+        None => (field.trim(), None),
+    };
+
+    let field = match name {
+        "r" => Field::Red,
+        "g" => Field::Green,
+        "b" => Field::Blue,
+        "a" => Field::Alpha,
+        "h" => Field::Hue,
+        "s" => Field::Saturation,
+        "l" => Field::Lightness,
+        "int" => Field::Integer,
+        _ => {
+            return Err(format!(
+                "Unknown field `{}` in color format `{}`. Valid fields are `{}`",
+                name,
+                template,
+                FIELDS.join("`, `")
+            ))
+        }
+    };
+
+    let invalid_notation = || {
+        format!(
+            "Invalid notation `{}` for field `{}` in color format `{}`",
+            notation.unwrap_or_default(),
+            name,
+            template
+        )
+    };
+
+    // Hexadecimal only makes sense for the 8 bits channels and the
+    // integer, floats for everything but the integer
+    let hex = !matches!(field, Field::Hue | Field::Saturation | Field::Lightness);
+    let notation = match notation {
+        None => Notation::Decimal,
+        Some("x") if hex => Notation::LowerHex,
+        Some("X") if hex => Notation::UpperHex,
+        Some("f") if field != Field::Integer => Notation::Float(None),
+        Some(notation) if field != Field::Integer => {
+            let decimals = notation
+                .strip_prefix('.')
+                .and_then(|n| n.strip_suffix('f'))
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(invalid_notation)?;
+            Notation::Float(Some(decimals))
+        }
+        Some(_) => return Err(invalid_notation()),
+    };
+
+    Ok(Segment::Field(field, notation))
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::blueprint::directive::*;
+
+    fn render(format: &str, rgb: RGB, alpha: u8) -> String {
+        ColorFormat::parse(format).unwrap().render(&rgb, alpha)
+    }
+
+    #[test]
+    fn presets() {
+        let color = RGB(0xAB, 0x0C, 0x80);
+        let render = |format| render(format, color.clone(), 0xCC);
+
+        assert_eq!(render(HEX_6_DIGITS_W_HASHTAG), "#AB0C80");
+        assert_eq!(render(HEX_6_DIGITS_WO_HASHTAG), "AB0C80");
+        assert_eq!(render(HEX_8_DIGITS_W_HASHTAG), "#AB0C80CC");
+        assert_eq!(render(HEX_8_DIGITS_WO_HASHTAG), "AB0C80CC");
+        assert_eq!(render(RGBA_FUNCTION), "rgba(171, 12, 128, 0.8)");
+        assert_eq!(render(HEX_6_DIGITS_LOWER_W_HASHTAG), "#ab0c80");
+        assert_eq!(render(HEX_6_DIGITS_LOWER_WO_HASHTAG), "ab0c80");
+        assert_eq!(render(HEX_6_DIGITS_W_0X), "0xab0c80");
+        assert_eq!(render(X11_RGB), "rgb:ab/0c/80");
+        assert_eq!(render(RGB_FUNCTION), "rgb(171, 12, 128)");
+        assert_eq!(render(HSL_FUNCTION), "hsl(316, 87%, 36%)");
+        assert_eq!(render(DECIMAL_COMMAS), "171,12,128");
+        assert_eq!(render(DECIMAL_SEMICOLONS), "171;12;128");
+        assert_eq!(render(FLOAT_CHANNELS), "0.671, 0.047, 0.502");
+        assert_eq!(render(INTEGER), "11209856");
+    }
+
+    #[test]
+    fn templates() {
+        let color = RGB(255, 0, 128);
+
+        assert_eq!(
+            render("{{ {r:.2f} {g:.2f} {b:.2f} }}", color.clone(), 255),
+            "{ 1.00 0.00 0.50 }"
+        );
+        assert_eq!(render("0x{a:X}{int:X}", color.clone(), 0), "0x00FF0080");
+        assert_eq!(render("{ h }-{s:f}-{l:.1f}", color, 0), "330-1-0.5");
+
+        // Grays have neither hue nor saturation
+        assert_eq!(render(HSL_FUNCTION, RGB(0, 0, 0), 0), "hsl(0, 0%, 0%)");
+    }
+
+    #[test]
+    fn invalid_formats() {
+        for format in [
+            "#4h",
+            "rgb({r}, {g",
+            "{r}}",
+            "{red}",
+            "{h:x}",
+            "{int:f}",
+            "{r:.f}",
+            "{g:e}",
+        ] {
+            assert!(ColorFormat::parse(format).is_err(), "{}", format);
+        }
+    }
+}
@@ -0,0 +1,554 @@
+// Parser of the CSS Color 4 notations, so that colorschemes can use
+// the colors as designers hand them over:
+//
+//     #abc  #abcd  #aabbcc  #aabbccdd
+//     rgb(40, 40, 40)  rgba(40 40 40 / 50%)
+//     hsl(210 20% 15%)  hsla(210deg, 20%, 15%, 0.5)
+//     hwb(210 10% 20%)
+//     oklch(62% 0.15 250)
+//     rebeccapurple  transparent
+//
+// Every notation is normalized to an RGBA color. Colors outside of the
+// sRGB gamut (e.g. from oklch) are clipped.
+
+use super::rgb::RGB;
+use super::rgba::RGBA;
+
+const FUNCTIONS: [&str; 6] = ["rgb", "rgba", "hsl", "hsla", "hwb", "oklch"];
+
+// Chroma that corresponds to 100% in oklch()
+const OKLCH_MAX_CHROMA: f32 = 0.4;
+
+// CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+// Value of a single component of a color function
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Component {
+    Number(f32),
+    Percentage(f32),
+    // Angle converted to degrees
+    Angle(f32),
+    // The `none` keyword, which behaves as zero
+    None,
+}
+
+// Parses any of the supported CSS notations
+pub fn parse(color: &str) -> Result<RGBA, String> {
+    let color = color.trim();
+
+    if color.starts_with('#') {
+        return parse_hex(color);
+    }
+
+    if let Some((name, args)) = color.split_once('(') {
+        let name = name.trim().to_lowercase();
+        let args = args
+            .strip_suffix(')')
+            .ok_or(format!("Missing closing parenthesis in `{}`", color))?;
+
+        return parse_function(&name, args)
+            .map_err(|e| format!("Invalid `{}()` color `{}`. {}", name, color, e));
+    }
+
+    named_color(color).ok_or(format!(
+        "Unknown color `{}`. Expected a hex color, a CSS color function (`{}()`) or a CSS named color",
+        color,
+        FUNCTIONS.join("()`, `")
+    ))
+}
+
+// Whether `name` is one of the CSS color functions
+pub fn is_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name.to_lowercase().as_str())
+}
+
+// Looks up a CSS named color (case insensitive)
+pub fn named_color(name: &str) -> Option<RGBA> {
+    let name = name.to_lowercase();
+
+    if name == "transparent" {
+        return Some(RGBA::from(RGB(0, 0, 0)).with_opacity(0));
+    }
+
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+        .ok()
+        .map(|index| {
+            let [_, r, g, b] = NAMED_COLORS[index].1.to_be_bytes();
+            RGB(r, g, b).into()
+        })
+}
+
+// #RGB, #RGBA, #RRGGBB and #RRGGBBAA
+fn parse_hex(color: &str) -> Result<RGBA, String> {
+    let digits = &color[1..];
+
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid hex color `{}`. `{}` is not a hex digit",
+            color, c
+        ));
+    }
+
+    // Short notations repeat each digit
+    let digits = match digits.len() {
+        3 | 4 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => digits.to_string(),
+        n => {
+            return Err(format!(
+                "Invalid hex color `{}`. Expected 3, 4, 6 or 8 hex digits, found {}",
+                color, n
+            ))
+        }
+    };
+
+    RGBA::new_from_hex(&digits)
+}
+
+fn parse_function(name: &str, args: &str) -> Result<RGBA, String> {
+    if !is_function(name) {
+        return Err(format!(
+            "Unknown color function. Valid functions are `{}()`",
+            FUNCTIONS.join("()`, `")
+        ));
+    }
+
+    let (components, alpha) = split_arguments(args)?;
+
+    let alpha = match alpha {
+        Some(alpha) => match parse_component(alpha)? {
+            Component::Number(a) if (0.0..=1.0).contains(&a) => a,
+            Component::Percentage(a) if (0.0..=100.0).contains(&a) => a / 100.0,
+            Component::None => 0.0,
+            _ => {
+                return Err(format!(
+                    "Invalid alpha `{}`. Expected a number between 0 and 1 or a percentage",
+                    alpha
+                ))
+            }
+        },
+        None => 1.0,
+    };
+
+    let [c1, c2, c3] = components;
+    let rgb = match name {
+        "rgb" | "rgba" => RGB(
+            rgb_channel("red", c1)?,
+            rgb_channel("green", c2)?,
+            rgb_channel("blue", c3)?,
+        ),
+        "hsl" | "hsla" => RGB::from_hsl(
+            hue(c1)?,
+            percentage("saturation", c2)? / 100.0,
+            percentage("lightness", c3)? / 100.0,
+        ),
+        "hwb" => hwb(
+            hue(c1)?,
+            percentage("whiteness", c2)? / 100.0,
+            percentage("blackness", c3)? / 100.0,
+        ),
+        "oklch" => RGB::from_oklch(oklch_lightness(c1)?, oklch_chroma(c2)?, hue(c3)?),
+        _ => unreachable!(),
+    };
+
+    Ok(RGBA {
+        rgb,
+        alpha: (alpha * 255.0).round() as u8,
+    })
+}
+
+// Splits the arguments into three components and an optional alpha.
+// Both the legacy syntax `rgb(r, g, b, a)` and the modern one
+// `rgb(r g b / a)` are accepted, but they can't be mixed
+fn split_arguments(args: &str) -> Result<([&str; 3], Option<&str>), String> {
+    let (components, alpha): (Vec<&str>, Option<&str>) = if args.contains(',') {
+        if args.contains('/') {
+            return Err("Commas and slashes can't be mixed".to_string());
+        }
+
+        let mut components: Vec<&str> = args.split(',').map(str::trim).collect();
+        if components.iter().any(|c| c.is_empty()) {
+            return Err("Empty component".to_string());
+        }
+
+        let alpha = match components.len() {
+            4 => components.pop(),
+            _ => None,
+        };
+        (components, alpha)
+    } else {
+        let mut parts = args.split('/');
+        let components = parts.next().unwrap().split_whitespace().collect();
+        let alpha = parts.next().map(str::trim);
+
+        if parts.next().is_some() {
+            return Err("Only one slash is allowed".to_string());
+        }
+        if alpha.is_some_and(|a| a.is_empty() || a.contains(char::is_whitespace)) {
+            return Err("Expected a single alpha value after the slash".to_string());
+        }
+        (components, alpha)
+    };
+
+    let count = components.len();
+    let components: [&str; 3] = components.try_into().map_err(|_| {
+        format!(
+            "Expected 3 components and an optional alpha, found {} components",
+            count
+        )
+    })?;
+
+    Ok((components, alpha))
+}
+
+fn parse_component(component: &str) -> Result<Component, String> {
+    if component.eq_ignore_ascii_case("none") {
+        return Ok(Component::None);
+    }
+
+    // Splits the number from its unit
+    let unit_start = component
+        .find(|c: char| c.is_alphabetic() || c == '%')
+        .unwrap_or(component.len());
+    let (number, unit) = component.split_at(unit_start);
+
+    let number: f32 = number
+        .parse()
+        .map_err(|_| format!("Invalid number `{}`", component))?;
+
+    match unit.to_lowercase().as_str() {
+        "" => Ok(Component::Number(number)),
+        "%" => Ok(Component::Percentage(number)),
+        "deg" => Ok(Component::Angle(number)),
+        "grad" => Ok(Component::Angle(number * 0.9)),
+        "rad" => Ok(Component::Angle(number.to_degrees())),
+        "turn" => Ok(Component::Angle(number * 360.0)),
+        _ => Err(format!(
+            "Invalid unit `{}` in `{}`. Valid units are `%`, `deg`, `grad`, `rad` and `turn`",
+            unit, component
+        )),
+    }
+}
+
+// Red, green or blue channel: a number between 0 and 255 or a
+// percentage
+fn rgb_channel(channel: &str, component: &str) -> Result<u8, String> {
+    match parse_component(component)? {
+        Component::Number(n) if (0.0..=255.0).contains(&n) => Ok(n.round() as u8),
+        Component::Percentage(p) if (0.0..=100.0).contains(&p) => Ok((p * 2.55).round() as u8),
+        Component::None => Ok(0),
+        _ => Err(format!(
+            "Invalid {} `{}`. Expected a number between 0 and 255 or a percentage",
+            channel, component
+        )),
+    }
+}
+
+// Hue in degrees: either a plain number or an angle
+fn hue(component: &str) -> Result<f32, String> {
+    match parse_component(component)? {
+        Component::Number(h) | Component::Angle(h) => Ok(h),
+        Component::None => Ok(0.0),
+        Component::Percentage(_) => Err(format!(
+            "Invalid hue `{}`. Expected a number or an angle",
+            component
+        )),
+    }
+}
+
+// Percentage between 0 and 100. The `%` sign can be omitted
+fn percentage(name: &str, component: &str) -> Result<f32, String> {
+    match parse_component(component)? {
+        Component::Number(p) | Component::Percentage(p) if (0.0..=100.0).contains(&p) => Ok(p),
+        Component::None => Ok(0.0),
+        _ => Err(format!(
+            "Invalid {} `{}`. Expected a percentage between 0% and 100%",
+            name, component
+        )),
+    }
+}
+
+// OKLCH lightness: a number between 0 and 1 or a percentage
+fn oklch_lightness(component: &str) -> Result<f32, String> {
+    match parse_component(component)? {
+        Component::Number(l) if (0.0..=1.0).contains(&l) => Ok(l),
+        Component::Percentage(l) if (0.0..=100.0).contains(&l) => Ok(l / 100.0),
+        Component::None => Ok(0.0),
+        _ => Err(format!(
+            "Invalid lightness `{}`. Expected a number between 0 and 1 or a percentage",
+            component
+        )),
+    }
+}
+
+// OKLCH chroma: a positive number or a percentage (100% = 0.4)
+fn oklch_chroma(component: &str) -> Result<f32, String> {
+    match parse_component(component)? {
+        Component::Number(c) if c >= 0.0 => Ok(c),
+        Component::Percentage(c) if c >= 0.0 => Ok(c / 100.0 * OKLCH_MAX_CHROMA),
+        Component::None => Ok(0.0),
+        _ => Err(format!(
+            "Invalid chroma `{}`. Expected a positive number or a percentage",
+            component
+        )),
+    }
+}
+
+// Converts hue, whiteness and blackness (in [0, 1]) to RGB
+fn hwb(hue: f32, whiteness: f32, blackness: f32) -> RGB {
+    // Too much white and black make a shade of gray
+    if whiteness + blackness >= 1.0 {
+        let gray = (whiteness / (whiteness + blackness) * 255.0).round() as u8;
+        return RGB(gray, gray, gray);
+    }
+
+    let RGB(r, g, b) = RGB::from_hsl(hue, 1.0, 0.5);
+    let _channel = |c: u8| {
+        ((c as f32 / 255.0 * (1.0 - whiteness - blackness) + whiteness) * 255.0).round() as u8
+    };
+
+    RGB(_channel(r), _channel(g), _channel(b))
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(color: &str) -> (u8, u8, u8, u8) {
+        let color = parse(color).unwrap();
+        (color.rgb.0, color.rgb.1, color.rgb.2, color.alpha)
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(rgba("#abc"), (0xAA, 0xBB, 0xCC, 0xFF));
+        assert_eq!(rgba("#abc8"), (0xAA, 0xBB, 0xCC, 0x88));
+        assert_eq!(rgba("#282828"), (0x28, 0x28, 0x28, 0xFF));
+        assert_eq!(rgba("#28282880"), (0x28, 0x28, 0x28, 0x80));
+    }
+
+    #[test]
+    fn rgb() {
+        assert_eq!(rgba("rgb(40, 40, 40)"), (40, 40, 40, 255));
+        assert_eq!(rgba("rgba(40, 40, 40, 0.5)"), (40, 40, 40, 128));
+        assert_eq!(rgba("rgb(40 40 40 / 50%)"), (40, 40, 40, 128));
+        assert_eq!(rgba("RGB(100% 0% none)"), (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn hsl() {
+        assert_eq!(rgba("hsl(210 20% 15%)"), (31, 38, 46, 255));
+        assert_eq!(rgba("hsla(0.5turn, 100%, 50%, 0.2)"), (0, 255, 255, 51));
+        assert_eq!(rgba("hsl(120deg 100 25)"), (0, 128, 0, 255));
+    }
+
+    #[test]
+    fn hwb() {
+        assert_eq!(rgba("hwb(0 0% 0%)"), (255, 0, 0, 255));
+        assert_eq!(rgba("hwb(120 20% 20%)"), (51, 204, 51, 255));
+        assert_eq!(rgba("hwb(0 60% 60%)"), (128, 128, 128, 255));
+    }
+
+    #[test]
+    fn oklch() {
+        assert_eq!(rgba("oklch(62.8% 0.2577 29.23)"), (255, 0, 0, 255));
+        assert_eq!(rgba("oklch(1 0 0)"), (255, 255, 255, 255));
+        assert_eq!(rgba("oklch(0% 0 none / 0.5)"), (0, 0, 0, 128));
+    }
+
+    #[test]
+    fn named() {
+        assert_eq!(rgba("rebeccapurple"), (0x66, 0x33, 0x99, 0xFF));
+        assert_eq!(rgba("AliceBlue"), (0xF0, 0xF8, 0xFF, 0xFF));
+        assert_eq!(rgba("transparent"), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |color: &str| parse(color).unwrap_err();
+
+        assert_eq!(
+            error("#12345"),
+            "Invalid hex color `#12345`. Expected 3, 4, 6 or 8 hex digits, found 5"
+        );
+        assert_eq!(
+            error("#12g"),
+            "Invalid hex color `#12g`. `g` is not a hex digit"
+        );
+        assert_eq!(
+            error("rgb(300, 0, 0)"),
+            "Invalid `rgb()` color `rgb(300, 0, 0)`. Invalid red `300`. Expected a number between 0 and 255 or a percentage"
+        );
+        assert_eq!(
+            error("rgb(0, 0)"),
+            "Invalid `rgb()` color `rgb(0, 0)`. Expected 3 components and an optional alpha, found 2 components"
+        );
+        assert_eq!(
+            error("rgb(0, 0, 0 / 1)"),
+            "Invalid `rgb()` color `rgb(0, 0, 0 / 1)`. Commas and slashes can't be mixed"
+        );
+        assert_eq!(
+            error("hsl(10px 0% 0%)"),
+            "Invalid `hsl()` color `hsl(10px 0% 0%)`. Invalid unit `px` in `10px`. Valid units are `%`, `deg`, `grad`, `rad` and `turn`"
+        );
+        assert_eq!(
+            error("hsl(10 0% 0% / 2)"),
+            "Invalid `hsl()` color `hsl(10 0% 0% / 2)`. Invalid alpha `2`. Expected a number between 0 and 1 or a percentage"
+        );
+        assert!(error("lab(50% 0 0)")
+            .starts_with("Invalid `lab()` color `lab(50% 0 0)`. Unknown color function"));
+        assert!(error("rgb(0, 0, 0").starts_with("Missing closing parenthesis"));
+        assert!(error("reddish").starts_with("Unknown color `reddish`"));
+    }
+}
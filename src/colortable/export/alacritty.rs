@@ -0,0 +1,60 @@
+// Exporter for Alacritty TOML themes (Alacritty >= 0.13). The output
+// can be imported from alacritty.toml through `general.import`
+
+use super::{color, hex};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+
+// Names of the normal/bright colors inside Alacritty themes
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut out = format!("# {}\n\n", name);
+
+    out.push_str("[colors.primary]\n");
+    out.push_str(&format!(
+        "background = '{}'\n",
+        hex(color(colors, BACKGROUND)?)
+    ));
+    out.push_str(&format!(
+        "foreground = '{}'\n",
+        hex(color(colors, FOREGROUND)?)
+    ));
+
+    out.push_str("\n[colors.cursor]\n");
+    out.push_str(&format!("cursor = '{}'\n", hex(color(colors, CURSOR)?)));
+    if let Some(rgb) = colors.get(CURSOR_TEXT) {
+        out.push_str(&format!("text = '{}'\n", hex(rgb)));
+    }
+
+    let selection = [
+        ("background", SELECTION_BACKGROUND),
+        ("text", SELECTION_FOREGROUND),
+    ];
+    if selection.iter().any(|(_, name)| colors.contains_key(*name)) {
+        out.push_str("\n[colors.selection]\n");
+        for (key, color_name) in selection {
+            if let Some(rgb) = colors.get(color_name) {
+                out.push_str(&format!("{} = '{}'\n", key, hex(rgb)));
+            }
+        }
+    }
+
+    for (group, names) in [
+        ("normal", &ANSI_COLOR_NAMES[..8]),
+        ("bright", &ANSI_COLOR_NAMES[8..]),
+    ] {
+        out.push_str(&format!("\n[colors.{}]\n", group));
+        for (key, color_name) in ANSI_NAMES.iter().zip(names) {
+            out.push_str(&format!(
+                "{} = '{}'\n",
+                key,
+                hex(color(colors, color_name)?)
+            ));
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,59 @@
+// Exporter for foot themes. The output can be included in foot.ini
+// through `include=`
+
+use super::{bare_hex, color};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut out = format!("# -*- conf -*-\n# {}\n\n", name);
+
+    // The text under the cursor defaults to the background color
+    let cursor_text = match colors.get(CURSOR_TEXT) {
+        Some(rgb) => rgb,
+        None => color(colors, BACKGROUND)?,
+    };
+    out.push_str("[cursor]\n");
+    out.push_str(&format!(
+        "color={} {}\n",
+        bare_hex(cursor_text),
+        bare_hex(color(colors, CURSOR)?)
+    ));
+
+    out.push_str("\n[colors]\n");
+    out.push_str(&format!(
+        "foreground={}\n",
+        bare_hex(color(colors, FOREGROUND)?)
+    ));
+    out.push_str(&format!(
+        "background={}\n",
+        bare_hex(color(colors, BACKGROUND)?)
+    ));
+
+    for (index, color_name) in ANSI_COLOR_NAMES.iter().enumerate() {
+        let key = if index < 8 {
+            format!("regular{}", index)
+        } else {
+            format!("bright{}", index - 8)
+        };
+        out.push_str(&format!(
+            "{}={}\n",
+            key,
+            bare_hex(color(colors, color_name)?)
+        ));
+    }
+
+    // Optional colors
+    let optional_settings = [
+        ("selection-foreground", SELECTION_FOREGROUND),
+        ("selection-background", SELECTION_BACKGROUND),
+        ("urls", URL),
+    ];
+    for (setting, color_name) in optional_settings {
+        if let Some(rgb) = colors.get(color_name) {
+            out.push_str(&format!("{}={}\n", setting, bare_hex(rgb)));
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,69 @@
+// Exporter for iTerm2 `.itermcolors` property lists. Each color is a
+// dictionary holding its components as floats between 0 and 1
+
+use super::color;
+use crate::colortable::rgb::RGB;
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+use plist::{Dictionary, Value};
+
+// chromasync colors and their iTerm2 counterparts
+const ITERM_MAPPING: [(&str, &str); 3] = [
+    (BACKGROUND, "Background Color"),
+    (FOREGROUND, "Foreground Color"),
+    (CURSOR, "Cursor Color"),
+];
+
+// Optional chromasync colors and their iTerm2 counterparts
+const OPTIONAL_ITERM_MAPPING: [(&str, &str); 5] = [
+    (SELECTION_BACKGROUND, "Selection Color"),
+    (SELECTION_FOREGROUND, "Selected Text Color"),
+    (CURSOR_TEXT, "Cursor Text Color"),
+    (BOLD, "Bold Color"),
+    (URL, "Link Color"),
+];
+
+// The name is not part of the format: iTerm2 names presets after the
+// file they're imported from
+pub fn export(_name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut plist = Dictionary::new();
+
+    for (name, iterm_name) in ITERM_MAPPING {
+        plist.insert(iterm_name.to_string(), dictionary(color(colors, name)?));
+    }
+
+    for (index, name) in ANSI_COLOR_NAMES.iter().enumerate() {
+        plist.insert(
+            format!("Ansi {} Color", index),
+            dictionary(color(colors, name)?),
+        );
+    }
+
+    for (name, iterm_name) in OPTIONAL_ITERM_MAPPING {
+        if let Some(rgb) = colors.get(name) {
+            plist.insert(iterm_name.to_string(), dictionary(rgb));
+        }
+    }
+
+    let mut out: Vec<u8> = Vec::new();
+    Value::Dictionary(plist)
+        .to_writer_xml(&mut out)
+        .map_err(|e| e.to_string())?;
+    out.push(b'\n');
+
+    String::from_utf8(out).map_err(|e| e.to_string())
+}
+
+// Converts an RGB tuple into an iTerm2 color dictionary
+fn dictionary(color: &RGB) -> Value {
+    let component = |c: u8| Value::Real(c as f64 / 255.0);
+
+    let mut dictionary = Dictionary::new();
+    dictionary.insert("Alpha Component".to_string(), Value::Real(1.0));
+    dictionary.insert("Blue Component".to_string(), component(color.2));
+    dictionary.insert("Color Space".to_string(), Value::String("sRGB".to_string()));
+    dictionary.insert("Green Component".to_string(), component(color.1));
+    dictionary.insert("Red Component".to_string(), component(color.0));
+
+    Value::Dictionary(dictionary)
+}
@@ -0,0 +1,47 @@
+// Exporter for kitty themes. The output can be included in kitty.conf
+// or stored among the kitty-themes
+
+use super::{color, hex};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut out = format!("## name: {}\n\n", name);
+
+    let settings = [
+        ("background", BACKGROUND),
+        ("foreground", FOREGROUND),
+        ("cursor", CURSOR),
+    ];
+    for (setting, color_name) in settings {
+        out.push_str(&format!(
+            "{} {}\n",
+            setting,
+            hex(color(colors, color_name)?)
+        ));
+    }
+
+    // Optional colors
+    let optional_settings = [
+        ("cursor_text_color", CURSOR_TEXT),
+        ("selection_background", SELECTION_BACKGROUND),
+        ("selection_foreground", SELECTION_FOREGROUND),
+        ("url_color", URL),
+    ];
+    for (setting, color_name) in optional_settings {
+        if let Some(rgb) = colors.get(color_name) {
+            out.push_str(&format!("{} {}\n", setting, hex(rgb)));
+        }
+    }
+
+    out.push('\n');
+    for (index, color_name) in ANSI_COLOR_NAMES.iter().enumerate() {
+        out.push_str(&format!(
+            "color{} {}\n",
+            index,
+            hex(color(colors, color_name)?)
+        ));
+    }
+
+    Ok(out)
+}
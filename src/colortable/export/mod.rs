@@ -0,0 +1,146 @@
+pub mod alacritty;
+pub mod foot;
+pub mod iterm;
+pub mod kitty;
+pub mod wezterm;
+pub mod windows_terminal;
+pub mod xresources;
+
+use super::rgb::RGB;
+use super::ColorTable;
+use crate::config::blueprint::directive::{HEX_6_DIGITS_WO_HASHTAG, HEX_6_DIGITS_W_HASHTAG};
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+
+// Formats colorschemes can be exported to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Kitty,
+    Alacritty,
+    Foot,
+    Wezterm,
+    Xresources,
+    Iterm,
+    WindowsTerminal,
+}
+
+// Names accepted by `--to`, with their aliases
+impl ValueEnum for Target {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Target::Kitty,
+            Target::Alacritty,
+            Target::Foot,
+            Target::Wezterm,
+            Target::Xresources,
+            Target::Iterm,
+            Target::WindowsTerminal,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Target::Kitty => PossibleValue::new("kitty"),
+            Target::Alacritty => PossibleValue::new("alacritty"),
+            Target::Foot => PossibleValue::new("foot"),
+            Target::Wezterm => PossibleValue::new("wezterm"),
+            Target::Xresources => PossibleValue::new("xresources").aliases(["xrdb", "xdefaults"]),
+            Target::Iterm => PossibleValue::new("iterm").aliases(["iterm2", "itermcolors"]),
+            Target::WindowsTerminal => PossibleValue::new("windows-terminal").aliases(["wt"]),
+        })
+    }
+}
+
+impl Target {
+    // Serializes a colorscheme named `name`
+    pub fn export(&self, name: &str, colors: &ColorTable) -> Result<String, String> {
+        match self {
+            Target::Kitty => kitty::export(name, colors),
+            Target::Alacritty => alacritty::export(name, colors),
+            Target::Foot => foot::export(name, colors),
+            Target::Wezterm => wezterm::export(name, colors),
+            Target::Xresources => xresources::export(name, colors),
+            Target::Iterm => iterm::export(name, colors),
+            Target::WindowsTerminal => windows_terminal::export(name, colors),
+        }
+    }
+}
+
+// Retrieves a color that must be present in the ColorTable
+fn color<'a>(colors: &'a ColorTable, name: &str) -> Result<&'a RGB, String> {
+    colors
+        .get(name)
+        .map(|color| &color.rgb)
+        .ok_or(format!("Missing required color `{}`", name))
+}
+
+// Formats a color as `#RRGGBB`
+fn hex(color: &RGB) -> String {
+    color.format(HEX_6_DIGITS_W_HASHTAG).unwrap()
+}
+
+// Formats a color as `RRGGBB`
+fn bare_hex(color: &RGB) -> String {
+    color.format(HEX_6_DIGITS_WO_HASHTAG).unwrap()
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::import::Source;
+    use crate::config::colorscheme::*;
+
+    // Builds a colorscheme where every color is different
+    fn colorscheme() -> ColorTable {
+        let mut colors = ColorTable::with_capacity(COLOR_NAMES.len() + 1);
+        for (index, name) in COLOR_NAMES.iter().enumerate() {
+            let index = index as u8;
+            colors.insert(name.to_string(), RGB(index, 2 * index, 255 - index).into());
+        }
+        colors.insert(SELECTION_BACKGROUND.to_string(), RGB(1, 2, 3).into());
+        colors
+    }
+
+    fn assert_same_colors(left: &ColorTable, right: &ColorTable) {
+        for name in COLOR_NAMES {
+            let (l, r) = (&left[name], &right[name]);
+            assert_eq!((l.0, l.1, l.2), (r.0, r.1, r.2), "Color `{}`", name);
+        }
+    }
+
+    // Exporting and importing back must give the same colors
+    #[test]
+    fn round_trip() {
+        let colors = colorscheme();
+        let round_trips = [
+            (Target::Kitty, Source::Kitty),
+            (Target::Alacritty, Source::Alacritty),
+            (Target::Foot, Source::Foot),
+            (Target::Wezterm, Source::Wezterm),
+            (Target::Xresources, Source::Xresources),
+            (Target::Iterm, Source::Iterm),
+            (Target::WindowsTerminal, Source::WindowsTerminal),
+        ];
+
+        for (target, source) in round_trips {
+            let exported = target.export("My Theme", &colors).unwrap();
+            let imported = source.import(&exported);
+            assert!(imported.is_ok(), "{:?}: {:?}", target, imported);
+
+            let imported = imported.unwrap();
+            assert_eq!(imported.len(), 1);
+            assert_same_colors(&colors, &imported[0].colors);
+        }
+    }
+
+    #[test]
+    fn missing_color() {
+        let mut colors = colorscheme();
+        colors.remove(COLOR_05);
+
+        for target in Target::value_variants() {
+            assert!(target.export("My Theme", &colors).is_err(), "{:?}", target);
+        }
+    }
+}
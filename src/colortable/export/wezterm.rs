@@ -0,0 +1,55 @@
+// Exporter for WezTerm TOML color schemes. The output can be stored
+// inside one of the `color_scheme_dirs`
+
+use super::{color, hex};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut out = String::from("[colors]\n");
+
+    let settings = [
+        ("background", BACKGROUND),
+        ("foreground", FOREGROUND),
+        ("cursor_bg", CURSOR),
+        ("cursor_border", CURSOR),
+    ];
+    for (setting, color_name) in settings {
+        out.push_str(&format!(
+            "{} = \"{}\"\n",
+            setting,
+            hex(color(colors, color_name)?)
+        ));
+    }
+
+    // Optional colors
+    let optional_settings = [
+        ("cursor_fg", CURSOR_TEXT),
+        ("selection_bg", SELECTION_BACKGROUND),
+        ("selection_fg", SELECTION_FOREGROUND),
+    ];
+    for (setting, color_name) in optional_settings {
+        if let Some(rgb) = colors.get(color_name) {
+            out.push_str(&format!("{} = \"{}\"\n", setting, hex(rgb)));
+        }
+    }
+
+    for (setting, names) in [
+        ("ansi", &ANSI_COLOR_NAMES[..8]),
+        ("brights", &ANSI_COLOR_NAMES[8..]),
+    ] {
+        let array = names
+            .iter()
+            .map(|color_name| color(colors, color_name).map(|rgb| format!("\"{}\"", hex(rgb))))
+            .collect::<Result<Vec<_>, _>>()?;
+        out.push_str(&format!("{} = [{}]\n", setting, array.join(", ")));
+    }
+
+    out.push_str("\n[metadata]\n");
+    out.push_str(&format!(
+        "name = {}\n",
+        toml::Value::String(name.to_string())
+    ));
+
+    Ok(out)
+}
@@ -0,0 +1,53 @@
+// Exporter for Windows Terminal color schemes. The output is a scheme
+// object to be added to the `schemes` array of settings.json
+
+use super::{color, hex};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+use serde_json::{Map, Value};
+
+// Names of the ANSI colors inside Windows Terminal schemes
+const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "purple",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightPurple",
+    "brightCyan",
+    "brightWhite",
+];
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut scheme = Map::new();
+    scheme.insert("name".to_string(), Value::String(name.to_string()));
+
+    let keys = [
+        ("background", BACKGROUND),
+        ("foreground", FOREGROUND),
+        ("cursorColor", CURSOR),
+    ];
+    let ansi_keys = ANSI_NAMES.iter().copied().zip(ANSI_COLOR_NAMES);
+
+    for (key, color_name) in keys.into_iter().chain(ansi_keys) {
+        let rgb = color(colors, color_name)?;
+        scheme.insert(key.to_string(), Value::String(hex(rgb)));
+    }
+
+    if let Some(rgb) = colors.get(SELECTION_BACKGROUND) {
+        scheme.insert("selectionBackground".to_string(), Value::String(hex(rgb)));
+    }
+
+    let mut out = serde_json::to_string_pretty(&scheme).map_err(|e| e.to_string())?;
+    out.push('\n');
+
+    Ok(out)
+}
@@ -0,0 +1,38 @@
+// Exporter for X resources. The output can be merged with
+// `xrdb -merge` or included in ~/.Xresources
+
+use super::{color, hex};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+
+pub fn export(name: &str, colors: &ColorTable) -> Result<String, String> {
+    let mut out = format!("! {}\n\n", name);
+
+    let resources = [
+        ("background", BACKGROUND),
+        ("foreground", FOREGROUND),
+        ("cursorColor", CURSOR),
+    ];
+    for (resource, color_name) in resources {
+        out.push_str(&format!(
+            "*.{}: {}\n",
+            resource,
+            hex(color(colors, color_name)?)
+        ));
+    }
+
+    if let Some(rgb) = colors.get(BOLD) {
+        out.push_str(&format!("*.colorBD: {}\n", hex(rgb)));
+    }
+
+    out.push('\n');
+    for (index, color_name) in ANSI_COLOR_NAMES.iter().enumerate() {
+        out.push_str(&format!(
+            "*.color{}: {}\n",
+            index,
+            hex(color(colors, color_name)?)
+        ));
+    }
+
+    Ok(out)
+}
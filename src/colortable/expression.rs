@@ -0,0 +1,393 @@
+// Expressions that can be used as colorscheme values instead of hex
+// colors, e.g. `foreground`, `lighten(color_01, 15%)` or
+// `mix(color_04, rgb(255 255 255), 80%)`. Any CSS color notation can be
+// used as a literal color.
+//
+// Expressions are parsed while reading the colorscheme and evaluated
+// once all the colors are known, since they can refer to any other
+// color of the colorscheme (or of its parents).
+
+use super::css;
+use super::rgba::RGBA;
+
+#[derive(Debug)]
+pub enum Expression {
+    // Literal color, e.g. `#FF8800`, `#F808` or `hsl(30 100% 50%)`
+    Color(RGBA),
+    // Name of another color (or of a CSS named color), with the column
+    // where it appears
+    Reference(String, usize),
+    // Function call. Arity and argument types are checked while parsing
+    Call(Function, Vec<Argument>),
+}
+
+#[derive(Debug)]
+pub enum Argument {
+    Color(Expression),
+    Number(f32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Function {
+    Mix,
+    Lighten,
+    Darken,
+    Saturate,
+    Desaturate,
+    HueRotate,
+}
+
+impl Function {
+    const NAMES: [&'static str; 6] = [
+        "mix",
+        "lighten",
+        "darken",
+        "saturate",
+        "desaturate",
+        "hue-rotate",
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mix" => Some(Self::Mix),
+            "lighten" => Some(Self::Lighten),
+            "darken" => Some(Self::Darken),
+            "saturate" => Some(Self::Saturate),
+            "desaturate" => Some(Self::Desaturate),
+            "hue-rotate" => Some(Self::HueRotate),
+            _ => None,
+        }
+    }
+
+    // Human readable signature, used in error messages
+    fn signature(&self) -> &'static str {
+        match self {
+            Self::Mix => "mix(color, color[, percentage])",
+            Self::Lighten => "lighten(color, percentage)",
+            Self::Darken => "darken(color, percentage)",
+            Self::Saturate => "saturate(color, percentage)",
+            Self::Desaturate => "desaturate(color, percentage)",
+            Self::HueRotate => "hue-rotate(color, degrees)",
+        }
+    }
+
+    // Whether the arguments match the signature of the function
+    fn accepts(&self, args: &[Argument]) -> bool {
+        match (self, args) {
+            (Self::Mix, [Argument::Color(_), Argument::Color(_)]) => true,
+            (Self::Mix, [Argument::Color(_), Argument::Color(_), Argument::Number(_)]) => true,
+            (Self::Mix, _) => false,
+            (_, [Argument::Color(_), Argument::Number(_)]) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Expression {
+    // Parses an expression. Errors report the column (starting from 1)
+    // where the problem was found
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+
+        let expression = parser.expression()?;
+
+        parser.skip_whitespaces();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("Unexpected trailing characters"));
+        }
+
+        Ok(expression)
+    }
+
+    // Evaluates the expression. References are looked up through
+    // `resolve`, which receives the color name and its column
+    pub fn evaluate<F>(&self, resolve: &mut F) -> Result<RGBA, String>
+    where
+        F: FnMut(&str, usize) -> Result<RGBA, String>,
+    {
+        match self {
+            Self::Color(color) => Ok(color.clone()),
+            Self::Reference(name, column) => resolve(name, *column),
+            Self::Call(function, args) => {
+                // Arguments were checked while parsing
+                let color = |index: usize, resolve: &mut F| match &args[index] {
+                    Argument::Color(expression) => expression.evaluate(resolve),
+                    Argument::Number(_) => unreachable!(),
+                };
+                let number = |index: usize| match args.get(index) {
+                    Some(Argument::Number(number)) => Some(*number),
+                    _ => None,
+                };
+
+                let first = color(0, resolve)?;
+                Ok(match function {
+                    Function::Mix => {
+                        let second = color(1, resolve)?;
+                        let amount = number(2).unwrap_or(50.0).clamp(0.0, 100.0);
+                        first.mix(amount.round() as u8, &second)
+                    }
+                    // The alpha channel is kept as it is
+                    Function::Lighten => first.map_rgb(|c| c.lighten(number(1).unwrap())),
+                    Function::Darken => first.map_rgb(|c| c.lighten(-number(1).unwrap())),
+                    Function::Saturate => first.map_rgb(|c| c.saturate(number(1).unwrap())),
+                    Function::Desaturate => first.map_rgb(|c| c.saturate(-number(1).unwrap())),
+                    Function::HueRotate => first.map_rgb(|c| c.hue_rotate(number(1).unwrap())),
+                })
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        format!("{} at column {}", message, self.pos + 1)
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    // Consumes the characters that satisfy the predicate
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&predicate) {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespaces();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("Expected `{}`", expected)));
+        }
+        self.pos += 1;
+
+        Ok(())
+    }
+
+    fn expression(&mut self) -> Result<Expression, String> {
+        self.skip_whitespaces();
+        let start = self.pos;
+
+        match self.peek() {
+            // Hex color
+            Some('#') => {
+                let hex = format!("#{}", {
+                    self.pos += 1;
+                    self.take_while(|c| c.is_ascii_alphanumeric())
+                });
+                css::parse(&hex).map(Expression::Color).map_err(|e| {
+                    self.pos = start;
+                    self.error(&e)
+                })
+            }
+            // Color name or function call
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '-');
+
+                self.skip_whitespaces();
+                if self.peek() != Some('(') {
+                    if name.contains('-') {
+                        self.pos = start;
+                        return Err(self.error(&format!("Invalid color name `{}`", name)));
+                    }
+                    return Ok(Expression::Reference(name, start + 1));
+                }
+
+                // CSS color functions, e.g. `rgb(40, 40, 40)`
+                if css::is_function(&name) {
+                    return self.css_function(start);
+                }
+
+                let function = Function::from_name(&name).ok_or_else(|| {
+                    self.pos = start;
+                    self.error(&format!(
+                        "Unknown function `{}` (expected one of `{}` or a CSS color function)",
+                        name,
+                        Function::NAMES.join("`, `")
+                    ))
+                })?;
+                self.pos += 1;
+
+                let args = self.arguments()?;
+                if !function.accepts(&args) {
+                    self.pos = start;
+                    return Err(self.error(&format!(
+                        "Invalid arguments, expected `{}`",
+                        function.signature()
+                    )));
+                }
+
+                Ok(Expression::Call(function, args))
+            }
+            Some(_) => Err(self.error("Expected a color")),
+            None => Err(self.error("Unexpected end of expression")),
+        }
+    }
+
+    // CSS color function starting at `start`, up to the closing
+    // parenthesis
+    fn css_function(&mut self, start: usize) -> Result<Expression, String> {
+        let end = self.chars[self.pos..]
+            .iter()
+            .position(|&c| c == ')')
+            .map(|offset| self.pos + offset + 1)
+            .ok_or_else(|| {
+                self.pos = self.chars.len();
+                self.error("Expected `)`")
+            })?;
+
+        let source: String = self.chars[start..end].iter().collect();
+        self.pos = end;
+
+        css::parse(&source).map(Expression::Color).map_err(|e| {
+            self.pos = start;
+            self.error(&e)
+        })
+    }
+
+    // Comma separated arguments, up to the closing parenthesis
+    fn arguments(&mut self) -> Result<Vec<Argument>, String> {
+        let mut args = Vec::new();
+
+        loop {
+            self.skip_whitespaces();
+            let arg = match self.peek() {
+                Some(c) if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                    Argument::Number(self.number()?)
+                }
+                _ => Argument::Color(self.expression()?),
+            };
+            args.push(arg);
+
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(')') => {
+                    self.pos += 1;
+                    return Ok(args);
+                }
+                _ => return self.expect(')').map(|_| args),
+            }
+        }
+    }
+
+    // Number with an optional `%` or `deg` unit
+    fn number(&mut self) -> Result<f32, String> {
+        let start = self.pos;
+
+        let mut digits = String::new();
+        if let Some(sign @ ('-' | '+')) = self.peek() {
+            digits.push(sign);
+            self.pos += 1;
+        }
+        digits.push_str(&self.take_while(|c| c.is_ascii_digit() || c == '.'));
+
+        let number: f32 = digits.parse().map_err(|_| {
+            self.pos = start;
+            self.error(&format!("Invalid number `{}`", digits))
+        })?;
+
+        // Optional unit
+        let unit_start = self.pos;
+        let unit = self.take_while(|c| c.is_alphabetic() || c == '%');
+        if !unit.is_empty() && unit != "%" && unit != "deg" {
+            self.pos = unit_start;
+            return Err(self.error(&format!("Invalid unit `{}`", unit)));
+        }
+
+        Ok(number)
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::rgb::RGB;
+
+    fn evaluate_rgba(source: &str) -> Result<RGBA, String> {
+        Expression::parse(source)?.evaluate(&mut |name, column| match name {
+            "black" => Ok(RGB(0, 0, 0).into()),
+            "red" => Ok(RGB(255, 0, 0).into()),
+            _ => Err(format!("Unknown color `{}` at column {}", name, column)),
+        })
+    }
+
+    fn evaluate(source: &str) -> Result<RGB, String> {
+        evaluate_rgba(source).map(|color| color.rgb)
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(evaluate("red"), Ok(RGB(255, 0, 0)));
+        assert_eq!(evaluate("  #0000FF "), Ok(RGB(0, 0, 255)));
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(evaluate("mix(red, black)"), Ok(RGB(128, 0, 0)));
+        assert_eq!(evaluate("mix(red, #000000, 100%)"), Ok(RGB(255, 0, 0)));
+        assert_eq!(evaluate("lighten(red, 50%)"), Ok(RGB(255, 255, 255)));
+        assert_eq!(evaluate("darken(red, 50)"), Ok(RGB(0, 0, 0)));
+        assert_eq!(evaluate("desaturate(red, 100%)"), Ok(RGB(128, 128, 128)));
+        assert_eq!(evaluate("hue-rotate(red, -120deg)"), Ok(RGB(0, 0, 255)));
+        assert_eq!(
+            evaluate("lighten(hue-rotate(red, 120), 0%)"),
+            Ok(RGB(0, 255, 0))
+        );
+    }
+
+    #[test]
+    fn alpha() {
+        let color = evaluate_rgba("lighten(#FF000080, 50%)").unwrap();
+        assert_eq!((color.rgb, color.alpha), (RGB(255, 255, 255), 0x80));
+
+        let color = evaluate_rgba("mix(#FF000000, red)").unwrap();
+        assert_eq!((color.rgb, color.alpha), (RGB(255, 0, 0), 128));
+    }
+
+    #[test]
+    fn error_locations() {
+        assert_eq!(
+            evaluate("lighten(blue, 10%)"),
+            Err("Unknown color `blue` at column 9".to_string())
+        );
+        assert!(evaluate("lightn(red, 10%)")
+            .unwrap_err()
+            .starts_with("Unknown function `lightn`"));
+        assert_eq!(
+            evaluate("lighten(red)"),
+            Err("Invalid arguments, expected `lighten(color, percentage)` at column 1".to_string())
+        );
+        assert_eq!(
+            evaluate("mix(red, black"),
+            Err("Expected `)` at column 15".to_string())
+        );
+        assert_eq!(
+            evaluate("lighten(red, 10px)"),
+            Err("Invalid unit `px` at column 16".to_string())
+        );
+        assert_eq!(
+            evaluate("red black"),
+            Err("Unexpected trailing characters at column 5".to_string())
+        );
+        assert!(evaluate("#12345").is_err());
+    }
+}
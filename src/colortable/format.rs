@@ -0,0 +1,194 @@
+use crate::config::colorscheme::{
+    FILE_EXTENSIONS, FILE_EXTENSION_JSON, FILE_EXTENSION_JSON5, FILE_EXTENSION_TOML,
+    FILE_EXTENSION_YAML, FILE_EXTENSION_YML,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+// File formats a colorscheme can be written in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Json5,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    // Detects the format of a colorscheme file from its extension
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+
+        match extension {
+            FILE_EXTENSION_JSON => Ok(Format::Json),
+            FILE_EXTENSION_JSON5 => Ok(Format::Json5),
+            FILE_EXTENSION_TOML => Ok(Format::Toml),
+            FILE_EXTENSION_YAML | FILE_EXTENSION_YML => Ok(Format::Yaml),
+            _ => Err(format!(
+                "Unsupported colorscheme file `{}`. Valid extensions are `{}`.",
+                path.display(),
+                FILE_EXTENSIONS.join("`, `")
+            )),
+        }
+    }
+
+    // Deserializes a string written in this format. All formats are
+    // driven through the same Deserialize implementation, hence the
+    // same validation rules apply to each of them
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, String> {
+        match self {
+            Format::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Format::Json5 => json5::from_str(content).map_err(|e| e.to_string()),
+            Format::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    // Serializes a value into a string written in this format
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, String> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            Format::Json5 => json5::to_string(value).map_err(|e| e.to_string()),
+            Format::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::ColorTable;
+    use std::path::PathBuf;
+
+    #[test]
+    fn detect_format() {
+        let format = |p: &str| Format::from_path(&PathBuf::from(p));
+        assert_eq!(format("a/b.json"), Ok(Format::Json));
+        assert_eq!(format("a/b.json5"), Ok(Format::Json5));
+        assert_eq!(format("a/b.toml"), Ok(Format::Toml));
+        assert_eq!(format("a/b.yaml"), Ok(Format::Yaml));
+        assert_eq!(format("a/b.yml"), Ok(Format::Yaml));
+        assert!(format("a/b.txt").is_err());
+        assert!(format("a/b").is_err());
+    }
+
+    #[test]
+    fn parse_toml() {
+        let toml = r##"
+            # Comments are allowed
+            background = "#000000"
+            foreground = "#000000"
+            cursor = "#000000"
+            color_01 = "#000000"
+            color_02 = "#000000"
+            color_03 = "#000000"
+            color_04 = "#000000"
+            color_05 = "#000000"
+            color_06 = "#000000"
+            color_07 = "#000000"
+            color_08 = "#000000"
+            color_09 = "#000000"
+            color_10 = "#000000"
+            color_11 = "#000000"
+            color_12 = "#000000"
+            color_13 = "#000000"
+            color_14 = "#000000"
+            color_15 = "#000000"
+            color_16 = "#000000"
+        "##;
+        let colorscheme = Format::Toml.parse::<ColorTable>(toml);
+        assert!(colorscheme.is_ok(), "{:?}", colorscheme);
+    }
+
+    #[test]
+    fn parse_toml_extra_colors() {
+        let toml = r##"
+            background = "#000000"
+            foreground = "#000000"
+            cursor = "#000000"
+            color_01 = "#000000"
+            color_02 = "#000000"
+            color_03 = "#000000"
+            color_04 = "#000000"
+            color_05 = "#000000"
+            color_06 = "#000000"
+            color_07 = "#000000"
+            color_08 = "#000000"
+            color_09 = "#000000"
+            color_10 = "#000000"
+            color_11 = "#000000"
+            color_12 = "#000000"
+            color_13 = "#000000"
+            color_14 = "#000000"
+            color_15 = "#000000"
+            color_16 = "#000000"
+
+            [extra]
+            accent = "#ff8800"
+        "##;
+        let colorscheme = Format::Toml.parse::<ColorTable>(toml).unwrap();
+        assert_eq!(colorscheme.extra_color_names(), vec!["accent"]);
+
+        // Serializing and parsing again gives back the extra section
+        let toml = Format::Toml.serialize(&colorscheme).unwrap();
+        let colorscheme = Format::Toml.parse::<ColorTable>(&toml).unwrap();
+        assert_eq!(colorscheme.extra_color_names(), vec!["accent"]);
+    }
+
+    #[test]
+    fn parse_yaml() {
+        let yaml = r##"
+            # Comments are allowed
+            background: "#000000"
+            foreground: "#000000"
+            cursor: "#000000"
+            color_01: "#000000"
+            color_02: "#000000"
+            color_03: "#000000"
+            color_04: "#000000"
+            color_05: "#000000"
+            color_06: "#000000"
+            color_07: "#000000"
+            color_08: "#000000"
+            color_09: "#000000"
+            color_10: "#000000"
+            color_11: "#000000"
+            color_12: "#000000"
+            color_13: "#000000"
+            color_14: "#000000"
+            color_15: "#000000"
+            color_16: "#000000"
+        "##;
+        let colorscheme = Format::Yaml.parse::<ColorTable>(yaml);
+        assert!(colorscheme.is_ok(), "{:?}", colorscheme);
+    }
+
+    #[test]
+    fn parse_json5() {
+        let json5 = r##"{
+            // Comments and trailing commas are allowed
+            background: "#000000", foreground: "#000000", cursor: "#000000",
+            color_01: "#000000", color_02: "#000000", color_03: "#000000",
+            color_04: "#000000", color_05: "#000000", color_06: "#000000",
+            color_07: "#000000", color_08: "#000000", color_09: "#000000",
+            color_10: "#000000", color_11: "#000000", color_12: "#000000",
+            color_13: "#000000", color_14: "#000000", color_15: "#000000",
+            color_16: "#000000",
+        }"##;
+        let colorscheme = Format::Json5.parse::<ColorTable>(json5);
+        assert!(colorscheme.is_ok(), "{:?}", colorscheme);
+    }
+
+    #[test]
+    fn invalid_color_in_toml() {
+        let toml = r##"background = "#00y000""##;
+        let colorscheme = Format::Toml.parse::<ColorTable>(toml);
+        assert!(colorscheme.is_err());
+    }
+}
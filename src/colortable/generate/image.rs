@@ -0,0 +1,312 @@
+// Extraction of the dominant colors of an image (PNG or JPEG).
+//
+// The image is scaled down, then its pixels are clustered with k-means
+// in OKLab, where euclidean distances match perceived differences. The
+// initial centers are picked deterministically (farthest point first),
+// so the same image always generates the same colorscheme.
+
+use super::{hue_difference, Palette};
+use crate::colortable::rgb::RGB;
+use crate::config::generate::*;
+use std::path::Path;
+
+// A group of similar colors: its mean in OKLab and the share of the
+// pixels it holds
+#[derive(Clone, Debug, PartialEq)]
+struct Cluster {
+    center: [f32; 3],
+    weight: f32,
+}
+
+// Decodes an image and builds a palette out of its dominant colors
+pub fn palette_from_image(path: &Path) -> Result<Palette, String> {
+    let mut image = ::image::ImageReader::open(path)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())?;
+
+    // Small images aren't scaled up, which would blend their colors
+    if image.width() > IMAGE_SIZE || image.height() > IMAGE_SIZE {
+        image = image.thumbnail(IMAGE_SIZE, IMAGE_SIZE);
+    }
+    let image = image.to_rgb8();
+
+    let pixels: Vec<[f32; 3]> = image
+        .pixels()
+        .map(|pixel| {
+            let (l, a, b) = RGB(pixel[0], pixel[1], pixel[2]).to_oklab();
+            [l, a, b]
+        })
+        .collect();
+
+    if pixels.is_empty() {
+        return Err("The image is empty".to_string());
+    }
+
+    Ok(palette_from_clusters(&kmeans(&pixels, PALETTE_SIZE)))
+}
+
+// Grays are tinted with the dominant color, and the backgrounds take the
+// lightness of the dominant dark and light colors. Each ANSI color takes the
+// hue of the most salient (chroma * weight) colorful cluster close to its
+// reference hue, pulled halfway towards the reference so that red still
+// looks red. When no cluster is close enough, the reference hue is used
+fn palette_from_clusters(clusters: &[Cluster]) -> Palette {
+    let chroma_hue = |cluster: &Cluster| {
+        let [_, a, b] = cluster.center;
+        (a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+    };
+
+    let tint = clusters.first().map(chroma_hue).unwrap_or_default();
+
+    // Clusters are sorted by weight: the first match is the dominant one
+    let dominant = |dark: bool| {
+        clusters
+            .iter()
+            .map(|cluster| cluster.center[0])
+            .find(|lightness| (*lightness < DARK_LIGHTNESS_THRESHOLD) == dark)
+    };
+
+    let colorful: Vec<(f32, f32, f32)> = clusters
+        .iter()
+        .map(|cluster| {
+            let (chroma, hue) = chroma_hue(cluster);
+            (chroma, hue, cluster.weight)
+        })
+        .filter(|(chroma, _, _)| *chroma >= MIN_CLUSTER_CHROMA)
+        .collect();
+
+    // Chroma of the reference hues: the average chroma of the image
+    let total_weight: f32 = colorful.iter().map(|(_, _, weight)| weight).sum();
+    let average_chroma = match total_weight > 0.0 {
+        true => {
+            colorful
+                .iter()
+                .map(|(chroma, _, weight)| chroma * weight)
+                .sum::<f32>()
+                / total_weight
+        }
+        false => MIN_ACCENT_CHROMA,
+    };
+
+    let mut accents = [(0.0, 0.0); 6];
+    for (accent, (_, _, reference)) in accents.iter_mut().zip(ACCENT_HUES) {
+        let salient = colorful
+            .iter()
+            .map(|&(chroma, hue, weight)| (chroma, hue_difference(reference, hue), weight))
+            .filter(|(_, difference, _)| difference.abs() <= MAX_HUE_DISTANCE)
+            .max_by(|(c1, _, w1), (c2, _, w2)| (c1 * w1).total_cmp(&(c2 * w2)));
+
+        *accent = match salient {
+            Some((chroma, difference, _)) => {
+                (chroma, (reference + difference / 2.0).rem_euclid(360.0))
+            }
+            None => (average_chroma, reference),
+        };
+    }
+
+    Palette {
+        tint,
+        accents,
+        lightness: None,
+        dark: dominant(true),
+        light: dominant(false),
+    }
+}
+
+// Groups the pixels into (at most) k clusters, sorted by weight
+fn kmeans(pixels: &[[f32; 3]], k: usize) -> Vec<Cluster> {
+    let mut centers = initial_centers(pixels, k);
+    let mut assignments = vec![usize::MAX; pixels.len()];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (assignment, pixel) in assignments.iter_mut().zip(pixels) {
+            let nearest = nearest_center(&centers, pixel);
+            if nearest != *assignment {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        // Moves the centers to the mean of their pixels
+        let mut sums = vec![([0.0_f32; 3], 0_usize); centers.len()];
+        for (&assignment, pixel) in assignments.iter().zip(pixels) {
+            let (sum, count) = &mut sums[assignment];
+            for (s, p) in sum.iter_mut().zip(pixel) {
+                *s += p;
+            }
+            *count += 1;
+        }
+        for (center, (sum, count)) in centers.iter_mut().zip(sums) {
+            if count > 0 {
+                *center = sum.map(|s| s / count as f32);
+            }
+        }
+    }
+
+    let mut counts = vec![0_usize; centers.len()];
+    for assignment in assignments {
+        counts[assignment] += 1;
+    }
+
+    let mut clusters: Vec<Cluster> = centers
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(center, count)| Cluster {
+            center,
+            weight: count as f32 / pixels.len() as f32,
+        })
+        .collect();
+    clusters.sort_by(|c1, c2| c2.weight.total_cmp(&c1.weight));
+
+    clusters
+}
+
+// Farthest point initialization: the first center is the mean color,
+// each next one the pixel farthest from the centers picked so far. Stops
+// early when the image has fewer than k distinct colors
+fn initial_centers(pixels: &[[f32; 3]], k: usize) -> Vec<[f32; 3]> {
+    let mut mean = [0.0_f32; 3];
+    for pixel in pixels {
+        for (m, p) in mean.iter_mut().zip(pixel) {
+            *m += p / pixels.len() as f32;
+        }
+    }
+
+    let mut centers = vec![mean];
+    let mut distances: Vec<f32> = pixels.iter().map(|p| distance(p, &mean)).collect();
+
+    while centers.len() < k {
+        let (index, &farthest) = distances
+            .iter()
+            .enumerate()
+            .max_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .unwrap();
+        if farthest == 0.0 {
+            break;
+        }
+
+        let center = pixels[index];
+        for (d, pixel) in distances.iter_mut().zip(pixels) {
+            *d = d.min(distance(pixel, &center));
+        }
+        centers.push(center);
+    }
+
+    centers
+}
+
+fn nearest_center(centers: &[[f32; 3]], pixel: &[f32; 3]) -> usize {
+    centers
+        .iter()
+        .enumerate()
+        .min_by(|(_, c1), (_, c2)| distance(pixel, c1).total_cmp(&distance(pixel, c2)))
+        .map(|(index, _)| index)
+        .unwrap()
+}
+
+// Squared euclidean distance
+fn distance(c1: &[f32; 3], c2: &[f32; 3]) -> f32 {
+    c1.iter().zip(c2).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::meta::{Meta, Variant};
+    use crate::config::colorscheme::BACKGROUND;
+
+    fn oklab(rgb: RGB) -> [f32; 3] {
+        let (l, a, b) = rgb.to_oklab();
+        [l, a, b]
+    }
+
+    #[test]
+    fn clusters() {
+        // 3/4 dark blue, 1/4 orange
+        let blue = oklab(RGB(20, 30, 60));
+        let orange = oklab(RGB(240, 150, 20));
+        let mut pixels = vec![blue; 300];
+        pixels.extend(vec![orange; 100]);
+
+        let clusters = kmeans(&pixels, PALETTE_SIZE);
+        assert_eq!(clusters.len(), 2);
+        assert!(distance(&clusters[0].center, &blue) < 1e-6);
+        assert_eq!(clusters[0].weight, 0.75);
+        assert!(distance(&clusters[1].center, &orange) < 1e-6);
+
+        let palette = palette_from_clusters(&clusters);
+        let (_, _, orange_hue) = RGB(240, 150, 20).to_oklch();
+        let (_, _, blue_hue) = RGB(20, 30, 60).to_oklch();
+
+        // Blue tint, red and yellow pulled towards orange, blue towards
+        // the blue of the image, the other colors keep their reference hue
+        assert!((palette.tint.1 - blue_hue).abs() < 0.01);
+        assert!((palette.accents[0].1 - (29.0 + orange_hue) / 2.0).abs() < 0.01);
+        assert!((palette.accents[2].1 - (100.0 + orange_hue) / 2.0).abs() < 0.01);
+        assert!((palette.accents[3].1 - (264.0 + blue_hue) / 2.0).abs() < 0.01);
+        assert_eq!(palette.accents[1].1, 142.0);
+    }
+
+    #[test]
+    fn background() {
+        let background = |dominant: RGB, variant: Variant| {
+            let pixels = [
+                vec![oklab(dominant); 300],
+                vec![oklab(RGB(240, 150, 20)); 100],
+            ];
+            let palette = palette_from_clusters(&kmeans(&pixels.concat(), PALETTE_SIZE));
+            let colors = palette.to_colortable(variant, Meta::default()).unwrap();
+            colors.get(BACKGROUND).unwrap().rgb.to_oklch().0
+        };
+
+        // The dominant dark color sets the background of dark
+        // colorschemes, within DARK_BACKGROUND_RANGE
+        let navy = background(RGB(10, 15, 30), Variant::Dark);
+        let slate = background(RGB(35, 40, 50), Variant::Dark);
+        assert!((navy - RGB(10, 15, 30).to_oklch().0).abs() < 0.01);
+        assert!((slate - RGB(35, 40, 50).to_oklch().0).abs() < 0.01);
+        assert!(slate - navy > 0.05);
+        let black = background(RGB(0, 0, 0), Variant::Dark);
+        assert!((black - DARK_BACKGROUND_RANGE.0).abs() < 0.01);
+
+        // And the dominant light color the one of light colorschemes
+        let cream = background(RGB(240, 230, 210), Variant::Light);
+        assert!((cream - RGB(240, 230, 210).to_oklch().0).abs() < 0.01);
+        let white = background(RGB(255, 255, 255), Variant::Light);
+        assert!((white - LIGHT_BACKGROUND_RANGE.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn decode() {
+        // Unique to this process, so that concurrent runs don't clash
+        let path = std::env::temp_dir().join(format!(
+            "chromasync-{}-generate-decode.png",
+            std::process::id()
+        ));
+        ::image::RgbImage::from_pixel(8, 8, ::image::Rgb([200, 40, 40]))
+            .save(&path)
+            .unwrap();
+
+        let palette = palette_from_image(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // A single red cluster tints the grays and the red
+        let palette = palette.unwrap();
+        let (_, chroma, hue) = RGB(200, 40, 40).to_oklch();
+        assert!((palette.tint.0 - chroma).abs() < 0.001);
+        assert!((palette.tint.1 - hue).abs() < 0.1);
+        assert!((palette.accents[0].1 - (29.0 + hue) / 2.0).abs() < 0.1);
+
+        assert!(palette_from_image(Path::new("missing.png")).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+// Generation of colorschemes out of a few seed colors, e.g. the dominant
+// colors of an image.
+//
+// Each hue gets a tonal ramp in OKLCH, whose lightness is perceptually
+// uniform. Colors are picked from the ramps, moving away from the
+// background until they meet the contrast ratios of config::generate.
+// Chroma is reduced when a tone falls outside of the sRGB gamut, so that
+// hues don't drift.
+
+pub mod image;
+pub mod seed;
+
+use super::meta::{Meta, Variant};
+use super::rgb::RGB;
+use super::ColorTable;
+use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
+use crate::config::check::MIN_CONTRAST_RATIO;
+use crate::config::colorscheme::*;
+use crate::config::generate::*;
+
+// Seed of a generated colorscheme, as OKLCH (chroma, hue) pairs
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    // Tint of the grays: background, foreground, black and white
+    pub tint: (f32, f32),
+    // Red, green, yellow, blue, magenta and cyan (see ACCENT_HUES)
+    pub accents: [(f32, f32); 6],
+    // Lightness the ANSI colors start from before the contrast ratios
+    // are met, e.g. the one of a seed color. Defaults to a lightness
+    // suited to the variant
+    pub lightness: Option<f32>,
+    // Lightness of the dominant dark and light colors, which the
+    // background of dark and light colorschemes starts from
+    pub dark: Option<f32>,
+    pub light: Option<f32>,
+}
+
+impl Palette {
+    // Builds a dark or light colorscheme out of the palette
+    pub fn to_colortable(&self, variant: Variant, meta: Meta) -> Result<ColorTable, String> {
+        let (tint_chroma, tint_hue) = (self.tint.0.min(MAX_TINT_CHROMA), self.tint.1);
+        let grays = Ramp::new(tint_chroma, tint_hue);
+
+        // Colors get lighter on dark backgrounds and darker on light ones
+        let (background_lightness, direction) = match variant {
+            Variant::Dark => (
+                self.dark.map_or(DARK_BACKGROUND_LIGHTNESS, |lightness| {
+                    lightness.clamp(DARK_BACKGROUND_RANGE.0, DARK_BACKGROUND_RANGE.1)
+                }),
+                1,
+            ),
+            Variant::Light => (
+                self.light.map_or(LIGHT_BACKGROUND_LIGHTNESS, |lightness| {
+                    lightness.clamp(LIGHT_BACKGROUND_RANGE.0, LIGHT_BACKGROUND_RANGE.1)
+                }),
+                -1,
+            ),
+        };
+        // Tone a number of steps away from the background
+        let away = |tone: usize, steps: isize| offset(tone, steps * direction);
+
+        // The background moves away from the far end of the gray ramp
+        // until they meet the foreground contrast ratio
+        let far_end = grays.get(match variant {
+            Variant::Dark => RAMP_SIZE - 1,
+            Variant::Light => 0,
+        });
+        let background_tone = grays.solve(
+            &far_end,
+            Ramp::tone(background_lightness),
+            FOREGROUND_CONTRAST,
+            -direction,
+        );
+        let background = grays.get(background_tone);
+        let solve = |ramp: &Ramp, start: usize, ratio: f32| {
+            ramp.solve(&background, start, ratio, direction)
+        };
+
+        let foreground_tone = solve(&grays, away(background_tone, 50), FOREGROUND_CONTRAST);
+        let foreground = grays.get(foreground_tone);
+
+        // Black blends with dark backgrounds, white with light ones
+        let (black, bright_black, white, bright_white) = match variant {
+            Variant::Dark => (
+                grays.get(away(background_tone, 6)),
+                grays.get(solve(&grays, away(background_tone, 20), MIN_CONTRAST_RATIO)),
+                grays.get(solve(&grays, offset(foreground_tone, -10), ACCENT_CONTRAST)),
+                grays.get(offset(foreground_tone, BRIGHT_TONE_STEP)),
+            ),
+            Variant::Light => (
+                foreground.clone(),
+                grays.get(solve(&grays, away(background_tone, 30), MIN_CONTRAST_RATIO)),
+                grays.get(away(background_tone, 10)),
+                grays.get(away(background_tone, 3)),
+            ),
+        };
+
+        let mut colors = vec![
+            (BACKGROUND, background.clone()),
+            (FOREGROUND, foreground.clone()),
+            (CURSOR, foreground),
+            (COLOR_01, black),
+            (COLOR_09, bright_black),
+            (COLOR_08, white),
+            (COLOR_16, bright_white),
+        ];
+
+        // Bright colors are lighter than the normal ones, and still
+        // readable on the background
+        let start = Ramp::tone(self.lightness.unwrap_or(match variant {
+            Variant::Dark => 0.7,
+            Variant::Light => 0.55,
+        }));
+        for ((normal, bright, _), (chroma, hue)) in ACCENT_HUES.iter().zip(self.accents) {
+            let ramp = Ramp::new(chroma.clamp(MIN_ACCENT_CHROMA, MAX_ACCENT_CHROMA), hue);
+            let normal_tone = solve(&ramp, start, ACCENT_CONTRAST);
+            let bright_tone = solve(
+                &ramp,
+                offset(normal_tone, BRIGHT_TONE_STEP),
+                MIN_CONTRAST_RATIO,
+            );
+
+            colors.push((normal, ramp.get(normal_tone)));
+            colors.push((bright, ramp.get(bright_tone)));
+        }
+
+        let mut hex_colors = Vec::with_capacity(colors.len());
+        for (name, color) in colors {
+            hex_colors.push((name.to_string(), color.format(HEX_6_DIGITS_W_HASHTAG)?));
+        }
+
+        let mut colortable = ColorTable::from_hex_map(hex_colors)?;
+        colortable.set_meta(meta);
+
+        Ok(colortable)
+    }
+}
+
+// Tonal ramp of a hue: RAMP_SIZE colors of evenly increasing OKLCH
+// lightness, from black to white. Every color of a colorscheme is a tone
+// of the ramp of the tint or of an accent
+struct Ramp(Vec<RGB>);
+
+impl Ramp {
+    fn new(chroma: f32, hue: f32) -> Self {
+        Ramp(
+            (0..RAMP_SIZE)
+                .map(|tone| oklch(Ramp::lightness(tone), chroma, hue))
+                .collect(),
+        )
+    }
+
+    // Lightness of a tone, and the tone closest to a lightness
+    fn lightness(tone: usize) -> f32 {
+        tone as f32 / (RAMP_SIZE - 1) as f32
+    }
+
+    fn tone(lightness: f32) -> usize {
+        (lightness.clamp(0.0, 1.0) * (RAMP_SIZE - 1) as f32).round() as usize
+    }
+
+    fn get(&self, tone: usize) -> RGB {
+        self.0[tone.min(RAMP_SIZE - 1)].clone()
+    }
+
+    // Moves along the ramp, one tone at a time in the given direction,
+    // until the contrast ratio against the background is met or the end
+    // of the ramp is reached
+    fn solve(&self, background: &RGB, start: usize, ratio: f32, direction: isize) -> usize {
+        let mut tone = start.min(RAMP_SIZE - 1);
+
+        while self.0[tone].contrast_ratio(background) < ratio {
+            match tone.checked_add_signed(direction) {
+                Some(next) if next < RAMP_SIZE => tone = next,
+                _ => break,
+            }
+        }
+
+        tone
+    }
+}
+
+// Signed difference from hue1 to hue2, in (-180, 180] degrees
+fn hue_difference(hue1: f32, hue2: f32) -> f32 {
+    let difference = (hue2 - hue1).rem_euclid(360.0);
+    match difference > 180.0 {
+        true => difference - 360.0,
+        false => difference,
+    }
+}
+
+// Tone a number of steps from another one, within the ramp
+fn offset(tone: usize, steps: isize) -> usize {
+    tone.saturating_add_signed(steps).min(RAMP_SIZE - 1)
+}
+
+// Converts an OKLCH color to RGB, reducing its chroma until it fits the
+// sRGB gamut. Clipping the channels would shift the hue and lightness
+fn oklch(lightness: f32, chroma: f32, hue: f32) -> RGB {
+    let mut chroma = chroma;
+
+    loop {
+        let color = RGB::from_oklch(lightness, chroma, hue);
+        let (l, c, _) = color.to_oklch();
+
+        if ((l - lightness).abs() < 0.01 && (c - chroma).abs() < 0.01) || chroma < 0.001 {
+            return color;
+        }
+        chroma *= 0.9;
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::check::NORMAL_BRIGHT_PAIRS;
+
+    const PALETTE: Palette = Palette {
+        tint: (0.05, 250.0),
+        accents: [
+            (0.2, 30.0),
+            (0.15, 140.0),
+            (0.15, 95.0),
+            (0.1, 260.0),
+            (0.3, 330.0),
+            (0.0, 200.0),
+        ],
+        lightness: None,
+        dark: None,
+        light: None,
+    };
+
+    fn rgb(colortable: &ColorTable, name: &str) -> RGB {
+        colortable.get(name).unwrap().rgb.clone()
+    }
+
+    #[test]
+    fn hue_differences() {
+        assert_eq!(hue_difference(29.0, 59.0), 30.0);
+        assert_eq!(hue_difference(59.0, 29.0), -30.0);
+        assert_eq!(hue_difference(350.0, 10.0), 20.0);
+        assert_eq!(hue_difference(10.0, 350.0), -20.0);
+    }
+
+    #[test]
+    fn ramps() {
+        let ramp = Ramp::new(0.15, 140.0);
+        assert_eq!(ramp.0.len(), RAMP_SIZE);
+        assert!(ramp.get(0).to_oklch().0 < 0.01);
+        assert!(ramp.get(RAMP_SIZE - 1).to_oklch().0 > 0.99);
+        assert_eq!(Ramp::tone(Ramp::lightness(42)), 42);
+
+        // Tones are ordered by lightness and keep the hue of the ramp
+        for pair in ramp.0.windows(2) {
+            assert!(pair[1].to_oklch().0 >= pair[0].to_oklch().0);
+        }
+        let (_, _, hue) = ramp.get(Ramp::tone(0.6)).to_oklch();
+        assert!((hue - 140.0).abs() < 2.0);
+
+        // Solving stops at the first tone meeting the ratio, or at the end
+        let background = ramp.get(20);
+        let tone = ramp.solve(&background, 20, 4.5, 1);
+        assert!(ramp.get(tone).contrast_ratio(&background) >= 4.5);
+        assert!(ramp.get(tone - 1).contrast_ratio(&background) < 4.5);
+        assert_eq!(ramp.solve(&background, 20, 100.0, 1), RAMP_SIZE - 1);
+        assert_eq!(ramp.solve(&background, 20, 100.0, -1), 0);
+    }
+
+    #[test]
+    fn slots() {
+        // Every color is a tone of the ramp of the tint or of its accent
+        let colors = PALETTE
+            .to_colortable(Variant::Dark, Meta::default())
+            .unwrap();
+        let grays = Ramp::new(PALETTE.tint.0.min(MAX_TINT_CHROMA), PALETTE.tint.1);
+        for name in [
+            BACKGROUND, FOREGROUND, COLOR_01, COLOR_08, COLOR_09, COLOR_16,
+        ] {
+            assert!(grays.0.contains(&rgb(&colors, name)), "{}", name);
+        }
+        for ((normal, bright, _), (chroma, hue)) in ACCENT_HUES.iter().zip(PALETTE.accents) {
+            let ramp = Ramp::new(chroma.clamp(MIN_ACCENT_CHROMA, MAX_ACCENT_CHROMA), hue);
+            assert!(ramp.0.contains(&rgb(&colors, normal)), "{}", normal);
+            assert!(ramp.0.contains(&rgb(&colors, bright)), "{}", bright);
+        }
+
+        // The background is the tone closest to the default lightness
+        assert_eq!(
+            rgb(&colors, BACKGROUND),
+            grays.get(Ramp::tone(DARK_BACKGROUND_LIGHTNESS))
+        );
+    }
+
+    #[test]
+    fn contrast() {
+        for variant in [Variant::Dark, Variant::Light] {
+            let colors = PALETTE.to_colortable(variant, Meta::default()).unwrap();
+            let background = rgb(&colors, BACKGROUND);
+
+            assert_eq!(colors.variant(), variant);
+            assert!(rgb(&colors, FOREGROUND).contrast_ratio(&background) >= FOREGROUND_CONTRAST);
+
+            for (normal, bright, _) in ACCENT_HUES {
+                let (normal, bright) = (rgb(&colors, normal), rgb(&colors, bright));
+                assert!(normal.contrast_ratio(&background) >= ACCENT_CONTRAST);
+                assert!(bright.contrast_ratio(&background) >= MIN_CONTRAST_RATIO);
+            }
+
+            // Bright colors are never darker than the normal ones
+            for (normal, bright) in NORMAL_BRIGHT_PAIRS {
+                let (normal, bright) = (rgb(&colors, normal), rgb(&colors, bright));
+                assert!(bright.relative_luminance() >= normal.relative_luminance());
+            }
+        }
+    }
+
+    #[test]
+    fn hues() {
+        let colors = PALETTE
+            .to_colortable(Variant::Dark, Meta::default())
+            .unwrap();
+
+        for ((normal, _, _), (chroma, hue)) in ACCENT_HUES.iter().zip(PALETTE.accents) {
+            let (_, c, h) = rgb(&colors, normal).to_oklch();
+            let distance = (h - hue).abs().min(360.0 - (h - hue).abs());
+            assert!(distance < 5.0, "{}: {} instead of {}", normal, h, hue);
+            assert!(c <= chroma.max(MIN_ACCENT_CHROMA) + 0.01);
+        }
+    }
+}
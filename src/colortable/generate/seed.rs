@@ -0,0 +1,175 @@
+// Generation of a palette around a single seed color.
+//
+// The ANSI hues are spread evenly on the OKLCH hue wheel (see
+// ACCENT_WHEEL_POSITIONS), and the wheel is rotated so that the hue
+// closest to the seed lands on it. The harmony picks which hues keep the
+// chroma of the seed; the others are muted so the seed stands out.
+
+use super::{hue_difference, Palette};
+use crate::colortable::rgb::RGB;
+use crate::config::generate::*;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+
+// Color harmonies a palette can be built with
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Harmony {
+    #[default]
+    Analogous,
+    Triadic,
+    Complementary,
+}
+
+// Names accepted by `--harmony`
+impl ValueEnum for Harmony {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Harmony::Analogous, Harmony::Triadic, Harmony::Complementary]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Harmony::Analogous => PossibleValue::new("analogous"),
+            Harmony::Triadic => PossibleValue::new("triadic"),
+            Harmony::Complementary => PossibleValue::new("complementary"),
+        })
+    }
+}
+
+impl Harmony {
+    // Hue offsets from the seed (see config::generate)
+    fn offsets(&self) -> &'static [f32] {
+        match self {
+            Harmony::Analogous => &ANALOGOUS_OFFSETS,
+            Harmony::Triadic => &TRIADIC_OFFSETS,
+            Harmony::Complementary => &COMPLEMENTARY_OFFSETS,
+        }
+    }
+}
+
+// Builds a palette around a seed color. The grays are tinted with the
+// seed, and the ANSI colors start from its lightness
+pub fn palette_from_seed(seed: &RGB, harmony: Harmony) -> Palette {
+    let (lightness, seed_chroma, hue) = seed.to_oklch();
+    let chroma = seed_chroma.clamp(MIN_ACCENT_CHROMA, MAX_ACCENT_CHROMA);
+
+    // The wheel starts from the reference hue of red
+    let wheel: Vec<f32> = ACCENT_WHEEL_POSITIONS
+        .iter()
+        .map(|position| ACCENT_HUES[0].2 + position * ACCENT_HUE_SPACING)
+        .collect();
+
+    // Grays have no meaningful hue: the wheel isn't rotated
+    let rotation = match seed_chroma < MIN_CLUSTER_CHROMA {
+        true => 0.0,
+        false => wheel
+            .iter()
+            .map(|&wheel_hue| hue_difference(wheel_hue, hue))
+            .min_by(|d1, d2| d1.abs().total_cmp(&d2.abs()))
+            .unwrap(),
+    };
+
+    let mut accents = [(0.0, 0.0); 6];
+    for (accent, wheel_hue) in accents.iter_mut().zip(wheel) {
+        let accent_hue = (wheel_hue + rotation).rem_euclid(360.0);
+        let in_harmony = harmony.offsets().iter().any(|offset| {
+            hue_difference(hue + offset, accent_hue).abs() < ACCENT_HUE_SPACING / 2.0
+        });
+
+        *accent = match in_harmony {
+            true => (chroma, accent_hue),
+            false => (chroma * MUTED_CHROMA_FACTOR, accent_hue),
+        };
+    }
+
+    Palette {
+        tint: (seed_chroma, hue),
+        accents,
+        lightness: Some(lightness),
+        dark: None,
+        light: None,
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::meta::{Meta, Variant};
+    use crate::config::colorscheme::BACKGROUND;
+
+    fn hues(palette: &Palette) -> Vec<f32> {
+        palette.accents.iter().map(|(_, hue)| *hue).collect()
+    }
+
+    #[test]
+    fn even_spacing() {
+        let seed = RGB::new_from_hex("#4a90e2").unwrap();
+        let (lightness, _, seed_hue) = seed.to_oklch();
+        let palette = palette_from_seed(&seed, Harmony::Analogous);
+
+        // Blue sits on the seed, the other hues are 60 degrees apart
+        let hues = hues(&palette);
+        assert!((hues[3] - seed_hue).abs() < 0.01);
+        let mut sorted = hues.clone();
+        sorted.sort_by(f32::total_cmp);
+        for pair in sorted.windows(2) {
+            assert!((pair[1] - pair[0] - ACCENT_HUE_SPACING).abs() < 0.01);
+        }
+
+        assert_eq!(palette.lightness, Some(lightness));
+        assert!((palette.tint.1 - seed_hue).abs() < 0.01);
+    }
+
+    #[test]
+    fn harmonies() {
+        let seed = RGB::new_from_hex("#4a90e2").unwrap();
+        let accented = |harmony| -> Vec<bool> {
+            let palette = palette_from_seed(&seed, harmony);
+            let chroma = palette.accents[3].0;
+            palette.accents.iter().map(|(c, _)| *c == chroma).collect()
+        };
+
+        // Red, green, yellow, blue, magenta, cyan
+        assert_eq!(
+            accented(Harmony::Analogous),
+            [false, false, false, true, true, true]
+        );
+        assert_eq!(
+            accented(Harmony::Triadic),
+            [true, true, false, true, false, false]
+        );
+        assert_eq!(
+            accented(Harmony::Complementary),
+            [false, false, true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn contrast() {
+        // Very dark and very light seeds still give readable colors
+        for hex in ["#4a90e2", "#0b0b2a", "#fff5c0"] {
+            let seed = RGB::new_from_hex(hex).unwrap();
+            let palette = palette_from_seed(&seed, Harmony::Triadic);
+
+            for variant in [Variant::Dark, Variant::Light] {
+                let colors = palette.to_colortable(variant, Meta::default()).unwrap();
+                let background = &colors.get(BACKGROUND).unwrap().rgb;
+                for (normal, _, _) in ACCENT_HUES {
+                    let color = &colors.get(normal).unwrap().rgb;
+                    assert!(color.contrast_ratio(background) >= ACCENT_CONTRAST);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gray_seed() {
+        let palette = palette_from_seed(&RGB(128, 128, 128), Harmony::Triadic);
+        let references: Vec<f32> = ACCENT_HUES.iter().map(|(_, _, hue)| *hue).collect();
+
+        // The hues stay close to the reference ones
+        for (hue, reference) in hues(&palette).iter().zip(references) {
+            assert!(hue_difference(*hue, reference).abs() < 15.0);
+        }
+    }
+}
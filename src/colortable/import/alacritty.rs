@@ -0,0 +1,156 @@
+// Importer for Alacritty themes. Those are TOML files since Alacritty
+// 0.13 and YAML files before then. Colors live inside the `colors`
+// table (`[colors.primary]`, `[colors.normal]`, ...) and are written
+// either as `#RRGGBB` or as `0xRRGGBB` (an integer when unquoted in YAML)
+
+use super::{finish_import, flatten, map_json_colors, ImportedColorscheme};
+use crate::config::colorscheme::*;
+
+// Names of the normal/bright colors inside Alacritty themes
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    // A TOML document is often a valid YAML document as well, but not
+    // the other way around. Hence TOML is tried first
+    let document: serde_json::Value = match toml::from_str(content) {
+        Ok(document) => document,
+        Err(_) => serde_yaml::from_str(content)
+            .map_err(|e| format!("The file is neither valid TOML nor valid YAML. {}", e))?,
+    };
+
+    let table = document
+        .get("colors")
+        .ok_or("Missing `colors` table".to_string())?;
+
+    let mut entries = Vec::new();
+    flatten("colors", table, &mut entries);
+
+    let (colors, report) = map_json_colors(entries, chromasync_name);
+
+    // Alacritty themes don't store the name of the colorscheme
+    finish_import(None, colors, report)
+}
+
+// Maps a flattened Alacritty key onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "colors.primary.background" => Some(BACKGROUND),
+        "colors.primary.foreground" => Some(FOREGROUND),
+        "colors.cursor.cursor" => Some(CURSOR),
+        "colors.cursor.text" => Some(CURSOR_TEXT),
+        "colors.selection.background" => Some(SELECTION_BACKGROUND),
+        "colors.selection.text" => Some(SELECTION_FOREGROUND),
+        _ => {
+            let (group, color) = key.strip_prefix("colors.")?.split_once('.')?;
+            let index = ANSI_NAMES.iter().position(|name| *name == color)?;
+
+            match group {
+                "normal" => Some(ANSI_COLOR_NAMES[index]),
+                "bright" => Some(ANSI_COLOR_NAMES[8 + index]),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALACRITTY_TOML: &str = r#"
+[colors.primary]
+background = '#1d1f21'
+foreground = '#c5c8c6'
+dim_foreground = '#828482'
+
+[colors.cursor]
+text = 'CellBackground'
+cursor = 'CellForeground'
+
+[colors.normal]
+black = '#1d1f21'
+red = '#cc6666'
+green = '#b5bd68'
+yellow = '#f0c674'
+blue = '#81a2be'
+magenta = '#b294bb'
+cyan = '#8abeb7'
+white = '#c5c8c6'
+
+[colors.bright]
+black = '#666666'
+red = '#d54e53'
+green = '#b9ca4a'
+yellow = '#e7c547'
+blue = '#7aa6da'
+magenta = '#c397d8'
+cyan = '#70c0b1'
+white = '#eaeaea'
+"#;
+
+    const ALACRITTY_YAML: &str = r#"
+colors:
+  primary:
+    background: '0x1d1f21'
+    foreground: '0xc5c8c6'
+  normal:
+    black:   '0x1d1f21'
+    red:     '0xcc6666'
+    green:   '0xb5bd68'
+    yellow:  '0xf0c674'
+    blue:    '0x81a2be'
+    magenta: '0xb294bb'
+    cyan:    '0x8abeb7'
+    white:   '0xc5c8c6'
+"#;
+
+    #[test]
+    fn import_alacritty_toml() {
+        let imported = import(ALACRITTY_TOML).unwrap();
+
+        let color = imported.colors.get(COLOR_10).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xd5, 0x4e, 0x53));
+
+        assert_eq!(
+            imported.report.dropped,
+            vec![
+                "colors.cursor.cursor",
+                "colors.cursor.text",
+                "colors.primary.dim_foreground"
+            ]
+        );
+        assert_eq!(
+            imported.report.derived,
+            vec![(CURSOR.to_string(), FOREGROUND.to_string())]
+        );
+    }
+
+    #[test]
+    fn import_alacritty_yaml() {
+        let imported = import(ALACRITTY_YAML).unwrap();
+
+        // Bright colors are derived from the normal ones
+        let color = imported.colors.get(COLOR_10).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xcc, 0x66, 0x66));
+        assert_eq!(imported.report.derived.len(), 9);
+    }
+
+    #[test]
+    fn integer_colors() {
+        // Unquoted hex values are read as integers by YAML parsers
+        let yaml = ALACRITTY_YAML.replace('\'', "");
+        let imported = import(&yaml).unwrap();
+
+        let color = imported.colors.get(COLOR_01).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0x1d, 0x1f, 0x21));
+        assert!(imported.report.dropped.is_empty());
+    }
+
+    #[test]
+    fn missing_colors_table() {
+        assert!(import("[window]\nopacity = 0.9").is_err());
+    }
+}
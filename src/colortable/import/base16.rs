@@ -0,0 +1,244 @@
+// Importer for base16 and base24 schemes. Both the legacy format (with
+// the base colors at the top level) and the tinted-theming format (with
+// the base colors nested inside `palette`) are supported.
+// See https://github.com/tinted-theming/home for the specification
+
+use super::{ImportReport, ImportedColorscheme};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::*;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+// Standard base16-shell mapping from base16 colors to chromasync colors
+const BASE16_MAPPING: [(&str, &str); 19] = [
+    (BACKGROUND, BASE_00),
+    (FOREGROUND, BASE_05),
+    (CURSOR, BASE_05),
+    (COLOR_01, BASE_00),
+    (COLOR_02, BASE_08),
+    (COLOR_03, BASE_0B),
+    (COLOR_04, BASE_0A),
+    (COLOR_05, BASE_0D),
+    (COLOR_06, BASE_0E),
+    (COLOR_07, BASE_0C),
+    (COLOR_08, BASE_05),
+    (COLOR_09, BASE_03),
+    (COLOR_10, BASE_08),
+    (COLOR_11, BASE_0B),
+    (COLOR_12, BASE_0A),
+    (COLOR_13, BASE_0D),
+    (COLOR_14, BASE_0E),
+    (COLOR_15, BASE_0C),
+    (COLOR_16, BASE_07),
+];
+
+// Standard base24-shell mapping. Bright colors come from base12-base17
+// instead of being copies of the normal ones
+const BASE24_MAPPING: [(&str, &str); 19] = [
+    (BACKGROUND, BASE_00),
+    (FOREGROUND, BASE_05),
+    (CURSOR, BASE_05),
+    (COLOR_01, BASE_00),
+    (COLOR_02, BASE_08),
+    (COLOR_03, BASE_0B),
+    (COLOR_04, BASE_0A),
+    (COLOR_05, BASE_0D),
+    (COLOR_06, BASE_0E),
+    (COLOR_07, BASE_0C),
+    (COLOR_08, BASE_06),
+    (COLOR_09, BASE_02),
+    (COLOR_10, BASE_12),
+    (COLOR_11, BASE_14),
+    (COLOR_12, BASE_13),
+    (COLOR_13, BASE_16),
+    (COLOR_14, BASE_17),
+    (COLOR_15, BASE_15),
+    (COLOR_16, BASE_07),
+];
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let document: Mapping = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+
+    // tinted-theming schemes nest the colors inside `palette`
+    let palette = match document.get("palette") {
+        Some(Value::Mapping(palette)) => palette,
+        _ => &document,
+    };
+
+    // Collects the base colors
+    let mut base_colors: HashMap<&str, String> = HashMap::new();
+    for (key, value) in palette {
+        let key = key.as_str().unwrap_or_default();
+
+        // Color names are matched case-insensitively (base0d == base0D)
+        let name = BASE_COLOR_NAMES
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(key));
+
+        if let Some(&name) = name {
+            base_colors.insert(name, hex_from_value(name, value)?);
+        }
+    }
+
+    // base00-base0F are mandatory
+    for &name in &BASE_COLOR_NAMES[..16] {
+        if !base_colors.contains_key(name) {
+            return Err(format!("Missing base16 color `{}`", name));
+        }
+    }
+
+    // A complete base24 palette has its own bright colors
+    let is_base24 = BASE_COLOR_NAMES[16..]
+        .iter()
+        .all(|name| base_colors.contains_key(name));
+    let mapping = if is_base24 {
+        &BASE24_MAPPING
+    } else {
+        &BASE16_MAPPING
+    };
+
+    // Maps the base colors onto the chromasync ones and keeps the
+    // original palette as well
+    let entries: Vec<(String, String)> = mapping
+        .iter()
+        .map(|(name, base_name)| (name.to_string(), base_colors[base_name].clone()))
+        .chain(
+            base_colors
+                .iter()
+                .map(|(name, hex)| (name.to_string(), hex.clone())),
+        )
+        .collect();
+
+    let colors = ColorTable::from_hex_map(entries)?;
+
+    // The scheme name is stored in `name` (tinted-theming) or `scheme`
+    // (legacy format)
+    let name = document
+        .get("name")
+        .or(document.get("scheme"))
+        .and_then(Value::as_str)
+        .map(|name| name.to_string());
+
+    Ok(ImportedColorscheme {
+        name,
+        colors,
+        report: ImportReport::default(),
+    })
+}
+
+// Converts a YAML value into an hex string. Unquoted hex colors made of
+// digits only (e.g. 002036) are parsed as numbers by YAML
+fn hex_from_value(name: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(hex) => Ok(hex.clone()),
+        Value::Number(number) if number.is_u64() => Ok(format!("{:06}", number)),
+        _ => Err(format!("Invalid value for base16 color `{}`", name)),
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::rgb::RGB;
+
+    const BASE16: &str = r##"
+scheme: "Default Dark"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "181818"
+base01: "282828"
+base02: "383838"
+base03: "585858"
+base04: "b8b8b8"
+base05: "d8d8d8"
+base06: "e8e8e8"
+base07: "f8f8f8"
+base08: "ab4642"
+base09: "dc9656"
+base0A: "f7ca88"
+base0B: "a1b56c"
+base0C: "86c1b9"
+base0D: "7cafc2"
+base0E: "ba8baf"
+base0F: "a16946"
+"##;
+
+    const BASE24: &str = r##"
+system: "base24"
+name: "Dracula"
+variant: "dark"
+palette:
+  base00: "#282a36"
+  base01: "#363447"
+  base02: "#44475a"
+  base03: "#6272a4"
+  base04: "#9ea8c7"
+  base05: "#f8f8f2"
+  base06: "#f0f1f4"
+  base07: "#ffffff"
+  base08: "#ff5555"
+  base09: "#ffb86c"
+  base0A: "#f1fa8c"
+  base0B: "#50fa7b"
+  base0C: "#8be9fd"
+  base0D: "#80bfff"
+  base0E: "#ff79c6"
+  base0F: "#bd93f9"
+  base10: "#1e2029"
+  base11: "#16171d"
+  base12: "#ff6e6e"
+  base13: "#ffffa5"
+  base14: "#69ff94"
+  base15: "#a4ffff"
+  base16: "#d6acff"
+  base17: "#ff92df"
+"##;
+
+    fn assert_color(colors: &ColorTable, name: &str, expected: RGB) {
+        let color = colors.get(name).unwrap();
+        assert!(
+            (color.0, color.1, color.2) == (expected.0, expected.1, expected.2),
+            "Wrong color `{}`: {:?}",
+            name,
+            color
+        );
+    }
+
+    #[test]
+    fn import_base16() {
+        let imported = import(BASE16).unwrap();
+        assert_eq!(imported.name.as_deref(), Some("Default Dark"));
+
+        let colors = &imported.colors;
+        assert_color(colors, BACKGROUND, RGB(0x18, 0x18, 0x18));
+        assert_color(colors, COLOR_02, RGB(0xab, 0x46, 0x42));
+        assert_color(colors, COLOR_09, RGB(0x58, 0x58, 0x58));
+        assert_color(colors, COLOR_10, RGB(0xab, 0x46, 0x42));
+        assert_color(colors, BASE_0F, RGB(0xa1, 0x69, 0x46));
+    }
+
+    #[test]
+    fn import_base24() {
+        let imported = import(BASE24).unwrap();
+        assert_eq!(imported.name.as_deref(), Some("Dracula"));
+
+        let colors = &imported.colors;
+        assert_color(colors, COLOR_08, RGB(0xf0, 0xf1, 0xf4));
+        assert_color(colors, COLOR_10, RGB(0xff, 0x6e, 0x6e));
+        assert_color(colors, COLOR_14, RGB(0xff, 0x92, 0xdf));
+        assert_color(colors, BASE_17, RGB(0xff, 0x92, 0xdf));
+    }
+
+    #[test]
+    fn unquoted_numeric_color() {
+        let yaml = BASE16.replace(r#"base00: "181818""#, "base00: 002036");
+        let imported = import(&yaml).unwrap();
+        assert_color(&imported.colors, BASE_00, RGB(0x00, 0x20, 0x36));
+    }
+
+    #[test]
+    fn missing_base_color() {
+        let yaml = BASE16.replace(r#"base0E: "ba8baf""#, "");
+        assert!(import(&yaml).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Importer for foot themes. Those are ini files where colors live in
+// the `[colors]` section and are written as `RRGGBB`. The cursor colors
+// are stored as a `text cursor` pair in `[cursor] color` (or in
+// `[colors] cursor` in recent versions of foot)
+
+use super::{finish_import, normalize_hex, ImportReport, ImportedColorscheme};
+use crate::config::colorscheme::*;
+use std::collections::HashMap;
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let mut colors = HashMap::new();
+    let mut report = ImportReport::default();
+
+    // Settings before the first section belong to [main]
+    let mut section = "main".to_string();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        // Skips empty lines and comments
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        // Section headers
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or(format!(
+            "Line {}: ill formed setting `{}`",
+            index + 1,
+            line
+        ))?;
+        let key = format!("{}.{}", section, key.trim());
+        let value = value.trim();
+
+        // The cursor setting holds two colors
+        if key == "cursor.color" || key == "colors.cursor" {
+            let pair: Vec<Option<String>> = value.split_whitespace().map(normalize_hex).collect();
+
+            if let [Some(text), Some(cursor)] = pair.as_slice() {
+                colors.insert(CURSOR_TEXT.to_string(), text.clone());
+                colors.insert(CURSOR.to_string(), cursor.clone());
+            } else {
+                report.dropped.push(key);
+            }
+            continue;
+        }
+
+        // Settings of other sections are not colors
+        if section != "colors" {
+            continue;
+        }
+
+        match (chromasync_name(&key), normalize_hex(value)) {
+            (Some(name), Some(hex)) => {
+                colors.insert(name.to_string(), hex);
+            }
+            _ => report.dropped.push(key),
+        }
+    }
+
+    // foot themes don't store the name of the colorscheme
+    finish_import(None, colors, report)
+}
+
+// Maps a foot setting onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "colors.background" => Some(BACKGROUND),
+        "colors.foreground" => Some(FOREGROUND),
+        "colors.selection-background" => Some(SELECTION_BACKGROUND),
+        "colors.selection-foreground" => Some(SELECTION_FOREGROUND),
+        "colors.urls" => Some(URL),
+        _ => {
+            let key = key.strip_prefix("colors.")?;
+            let (offset, index) = match key.strip_prefix("regular") {
+                Some(index) => (0, index),
+                None => (8, key.strip_prefix("bright")?),
+            };
+            let index: usize = index.parse().ok().filter(|index| *index < 8)?;
+
+            Some(ANSI_COLOR_NAMES[offset + index])
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOOT: &str = "
+# -*- conf -*-
+# Zenburn
+
+[cursor]
+color=111111 dcdccc
+
+[colors]
+foreground=dcdccc
+background=111111
+alpha=0.9
+
+regular0=222222
+regular1=cc9393
+regular2=7f9f7f
+regular3=d0bf8f
+regular4=6ca0a3
+regular5=dc8cc3
+regular6=93e0e3
+regular7=dcdccc
+
+bright0=666666
+bright1=dca3a3
+bright2=bfebbf
+bright3=f0dfaf
+bright4=8cd0d3
+bright5=fcace3
+bright6=b3ffff
+bright7=ffffff
+
+dim0=555555
+";
+
+    #[test]
+    fn import_foot() {
+        let imported = import(FOOT).unwrap();
+
+        let color = imported.colors.get(CURSOR).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xdc, 0xdc, 0xcc));
+        let color = imported.colors.get(CURSOR_TEXT).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0x11, 0x11, 0x11));
+        let color = imported.colors.get(COLOR_16).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xff, 0xff, 0xff));
+
+        assert_eq!(imported.report.dropped, vec!["colors.alpha", "colors.dim0"]);
+        assert!(imported.report.derived.is_empty());
+    }
+
+    #[test]
+    fn ill_formed_setting() {
+        assert!(import("[colors]\nforeground dcdccc").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+// Importer for Gogh palettes. Gogh uses the same color names as
+// chromasync (`color_01`-`color_16`, `background`, `foreground` and
+// `cursor`). A file may hold a single theme, an array of themes or an
+// object with a `themes` array
+
+use super::{finish_import, map_json_colors, ImportedColorscheme};
+use crate::config::colorscheme::*;
+use serde_json::Value;
+
+pub fn import(content: &str) -> Result<Vec<ImportedColorscheme>, String> {
+    let document: Value = json5::from_str(content).map_err(|e| e.to_string())?;
+
+    // An empty `themes` array is still recognized, but there's nothing
+    // to import
+    themes(&document)
+        .filter(|themes| !themes.is_empty())
+        .ok_or("No Gogh theme found".to_string())?
+        .iter()
+        .enumerate()
+        .map(|(index, theme)| {
+            import_theme(theme).map_err(|e| format!("Theme #{}. {}", index + 1, e))
+        })
+        .collect()
+}
+
+// Whether the document looks like a Gogh file
+pub fn is_gogh(document: &Value) -> bool {
+    themes(document).is_some()
+}
+
+// Finds the themes inside the document
+fn themes(document: &Value) -> Option<Vec<&Value>> {
+    let is_theme = |value: &Value| value.get(COLOR_01).is_some();
+
+    match document {
+        Value::Object(object) if object.contains_key("themes") => {
+            Some(object["themes"].as_array()?.iter().collect())
+        }
+        Value::Object(_) if is_theme(document) => Some(vec![document]),
+        Value::Array(array) if !array.is_empty() && array.iter().all(is_theme) => {
+            Some(array.iter().collect())
+        }
+        _ => None,
+    }
+}
+
+fn import_theme(theme: &Value) -> Result<ImportedColorscheme, String> {
+    let theme = theme
+        .as_object()
+        .ok_or("The theme is not an object".to_string())?;
+
+    let name = theme
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_string());
+
+    let entries = theme
+        .iter()
+        .filter(|(key, _)| *key != "name")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let (colors, report) = map_json_colors(entries, chromasync_name);
+
+    finish_import(name, colors, report)
+}
+
+// Gogh keys are already chromasync color names
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    COLOR_NAMES.iter().find(|name| **name == key).copied()
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOGH: &str = r##"{
+  "themes": [
+    {
+      "name": "Dracula",
+      "color_01": "#262626", "color_02": "#E64747", "color_03": "#42E66C", "color_04": "#E4F34A",
+      "color_05": "#9B6BDF", "color_06": "#E356A7", "color_07": "#75D7EC", "color_08": "#EFA554",
+      "color_09": "#7A7A7A", "color_10": "#FF5555", "color_11": "#50FA7B", "color_12": "#F1FA8C",
+      "color_13": "#BD93F9", "color_14": "#FF79C6", "color_15": "#8BE9FD", "color_16": "#FFB86C",
+      "background": "#282A36", "foreground": "#94A3A5", "cursor": "#94A3A5",
+      "hash": "dracula"
+    }
+  ]
+}"##;
+
+    #[test]
+    fn import_gogh() {
+        let imported = import(GOGH).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name.as_deref(), Some("Dracula"));
+
+        let color = imported[0].colors.get(COLOR_16).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xff, 0xb8, 0x6c));
+        assert_eq!(imported[0].report.dropped, vec!["hash"]);
+    }
+
+    #[test]
+    fn empty_themes() {
+        assert!(import(r#"{"themes": []}"#).is_err());
+    }
+}
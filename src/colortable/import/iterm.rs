@@ -0,0 +1,228 @@
+// Importer for iTerm2 `.itermcolors` files. Those are XML property
+// lists mapping each color name (e.g. "Ansi 0 Color") to a dictionary
+// holding its red/green/blue components as numbers between 0 and 1
+
+use super::{ImportReport, ImportedColorscheme};
+use crate::colortable::rgb::RGB;
+use crate::colortable::ColorTable;
+use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
+use crate::config::colorscheme::*;
+use plist::{Dictionary, Value};
+
+// Color space the components are read in. Other spaces (e.g. `P3`) are
+// read the same way, with a note in the report
+const SRGB_COLOR_SPACE: &str = "sRGB";
+
+// iTerm2 color names and their chromasync counterparts
+const ITERM_MAPPING: [(&str, &str); 24] = [
+    ("Background Color", BACKGROUND),
+    ("Foreground Color", FOREGROUND),
+    ("Cursor Color", CURSOR),
+    ("Ansi 0 Color", COLOR_01),
+    ("Ansi 1 Color", COLOR_02),
+    ("Ansi 2 Color", COLOR_03),
+    ("Ansi 3 Color", COLOR_04),
+    ("Ansi 4 Color", COLOR_05),
+    ("Ansi 5 Color", COLOR_06),
+    ("Ansi 6 Color", COLOR_07),
+    ("Ansi 7 Color", COLOR_08),
+    ("Ansi 8 Color", COLOR_09),
+    ("Ansi 9 Color", COLOR_10),
+    ("Ansi 10 Color", COLOR_11),
+    ("Ansi 11 Color", COLOR_12),
+    ("Ansi 12 Color", COLOR_13),
+    ("Ansi 13 Color", COLOR_14),
+    ("Ansi 14 Color", COLOR_15),
+    ("Ansi 15 Color", COLOR_16),
+    // Optional colors
+    ("Selection Color", SELECTION_BACKGROUND),
+    ("Selected Text Color", SELECTION_FOREGROUND),
+    ("Cursor Text Color", CURSOR_TEXT),
+    ("Bold Color", BOLD),
+    ("Link Color", URL),
+];
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let plist = Value::from_reader_xml(content.as_bytes()).map_err(|e| e.to_string())?;
+    let plist = plist
+        .as_dictionary()
+        .ok_or("The property list is not a dictionary".to_string())?;
+
+    // Converts every known color. Missing required colors are reported
+    // by the ColorTable validation
+    let mut entries: Vec<(String, String)> = Vec::with_capacity(ITERM_MAPPING.len());
+    let mut report = ImportReport::default();
+    for (iterm_name, name) in ITERM_MAPPING {
+        if let Some(color) = plist.get(iterm_name) {
+            let color = color
+                .as_dictionary()
+                .ok_or(format!("Color `{}` is not a dictionary", iterm_name))?;
+            let rgb = rgb_from_dictionary(color)
+                .map_err(|e| format!("Invalid color `{}`. {}", iterm_name, e))?;
+
+            let color_space = color.get("Color Space").and_then(Value::as_string);
+            if let Some(color_space) = color_space.filter(|space| *space != SRGB_COLOR_SPACE) {
+                report.notes.push(format!(
+                    "Color `{}` is in the `{}` color space. Its components were read as {}",
+                    iterm_name, color_space, SRGB_COLOR_SPACE
+                ));
+            }
+
+            entries.push((name.to_string(), rgb.format(HEX_6_DIGITS_W_HASHTAG)?));
+        }
+    }
+
+    let colors = ColorTable::from_hex_map(entries)?;
+
+    // Reports the colors without a chromasync counterpart
+    for iterm_name in plist.keys() {
+        if !ITERM_MAPPING.iter().any(|(name, _)| name == iterm_name) {
+            report.dropped.push(iterm_name.clone());
+        }
+    }
+    report.dropped.sort();
+
+    // .itermcolors files don't store the name of the colorscheme
+    Ok(ImportedColorscheme {
+        name: None,
+        colors,
+        report,
+    })
+}
+
+// Converts a dictionary with "Red/Green/Blue Component" entries into an
+// RGB tuple. Components are usually `<real>`, but `<integer>` 0 and 1
+// are found too
+fn rgb_from_dictionary(color: &Dictionary) -> Result<RGB, String> {
+    let component = |key: &str| -> Result<u8, String> {
+        let value = color
+            .get(key)
+            .and_then(|value| {
+                value
+                    .as_real()
+                    .or(value.as_signed_integer().map(|value| value as f64))
+            })
+            .ok_or(format!("Missing `{}`", key))?;
+
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("`{}` is out of range: {}", key, value));
+        }
+
+        Ok((value * 255.0).round() as u8)
+    };
+
+    Ok(RGB(
+        component("Red Component")?,
+        component("Green Component")?,
+        component("Blue Component")?,
+    ))
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds an .itermcolors file where all colors are black, except for
+    // the ones passed as arguments
+    fn itermcolors(colors: &[(&str, f64, f64, f64)]) -> String {
+        let mut plist = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+"#,
+        );
+
+        let black = ITERM_MAPPING[..19]
+            .iter()
+            .filter(|(name, _)| !colors.iter().any(|c| c.0 == *name))
+            .map(|(name, _)| (*name, 0.0, 0.0, 0.0));
+
+        for (name, r, g, b) in colors.iter().copied().chain(black) {
+            plist.push_str(&format!(
+                r#"	<key>{name}</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>{b}</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>{g}</real>
+		<key>Red Component</key>
+		<real>{r}</real>
+	</dict>
+"#
+            ));
+        }
+
+        plist.push_str("</dict>\n</plist>\n");
+        plist
+    }
+
+    #[test]
+    fn import_itermcolors() {
+        let content = itermcolors(&[
+            ("Ansi 1 Color", 1.0, 0.5, 0.0),
+            ("Background Color", 0.156862, 0.156862, 0.156862),
+            ("Selection Color", 0.2, 0.4, 0.6),
+        ]);
+        let imported = import(&content).unwrap();
+
+        let red = imported.colors.get(COLOR_02).unwrap();
+        assert_eq!((red.0, red.1, red.2), (255, 128, 0));
+
+        let background = imported.colors.get(BACKGROUND).unwrap();
+        assert_eq!((background.0, background.1, background.2), (40, 40, 40));
+
+        let selection = imported.colors.get(SELECTION_BACKGROUND).unwrap();
+        assert_eq!((selection.0, selection.1, selection.2), (51, 102, 153));
+    }
+
+    #[test]
+    fn report_dropped_colors() {
+        let content = itermcolors(&[("Badge Color", 1.0, 0.0, 0.0)]);
+        let imported = import(&content).unwrap();
+        assert_eq!(imported.report.dropped, vec!["Badge Color".to_string()]);
+    }
+
+    #[test]
+    fn integer_components() {
+        let content = itermcolors(&[("Ansi 1 Color", 1.0, 0.0, 0.0)]).replace(
+            "<real>1</real>\n\t\t<key>Blue Component</key>\n\t\t<real>0</real>",
+            "<real>1</real>\n\t\t<key>Blue Component</key>\n\t\t<integer>0</integer>",
+        );
+        assert!(content.contains("<integer>0</integer>"));
+
+        let imported = import(&content).unwrap();
+        let red = imported.colors.get(COLOR_02).unwrap();
+        assert_eq!((red.0, red.1, red.2), (255, 0, 0));
+        assert!(imported.report.notes.is_empty());
+    }
+
+    #[test]
+    fn color_space_note() {
+        let content = itermcolors(&[("Ansi 1 Color", 1.0, 0.0, 0.0)]).replacen(
+            "<string>sRGB</string>",
+            "<string>P3</string>",
+            1,
+        );
+        let imported = import(&content).unwrap();
+        assert_eq!(imported.report.notes.len(), 1);
+        assert!(imported.report.notes[0].contains("`P3`"));
+    }
+
+    #[test]
+    fn out_of_range_component() {
+        let content = itermcolors(&[("Ansi 1 Color", 1.5, 0.0, 0.0)]);
+        assert!(import(&content).is_err());
+    }
+
+    #[test]
+    fn missing_color() {
+        let content = itermcolors(&[]).replace("Cursor Color", "Cursor Guide Color");
+        assert!(import(&content).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+// Importer for kitty themes, such as the ones of kitty-themes. Each
+// line holds `name value` and comments start with `#`. kitty-themes
+// store their metadata in comments like `## name: Dracula`
+
+use super::{finish_import, normalize_hex, ImportReport, ImportedColorscheme};
+use crate::config::colorscheme::*;
+use std::collections::HashMap;
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let mut name = None;
+    let mut colors = HashMap::new();
+    let mut report = ImportReport::default();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        // Metadata
+        if let Some((key, value)) = line.strip_prefix("##").and_then(|m| m.split_once(':')) {
+            if key.trim() == "name" {
+                name = Some(value.trim().to_string());
+            }
+            continue;
+        }
+
+        // Skips empty lines and comments
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+        match (chromasync_name(key), normalize_hex(value)) {
+            (Some(name), Some(hex)) => {
+                colors.insert(name.to_string(), hex);
+            }
+            // Unknown settings and special values (e.g. `cursor none`)
+            _ => report.dropped.push(key.to_string()),
+        }
+    }
+
+    finish_import(name, colors, report)
+}
+
+// Maps a kitty setting onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "background" => Some(BACKGROUND),
+        "foreground" => Some(FOREGROUND),
+        "cursor" => Some(CURSOR),
+        "cursor_text_color" => Some(CURSOR_TEXT),
+        "selection_background" => Some(SELECTION_BACKGROUND),
+        "selection_foreground" => Some(SELECTION_FOREGROUND),
+        "url_color" => Some(URL),
+        _ => key
+            .strip_prefix("color")
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| ANSI_COLOR_NAMES.get(index).copied()),
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KITTY: &str = "
+# vim:ft=kitty
+
+## name: Tokyo Night
+## author: enkia
+
+background #1a1b26
+foreground #c0caf5
+selection_background #283457
+selection_foreground #c0caf5
+url_color #73daca
+cursor none
+active_border_color #7aa2f7
+
+# normal
+color0 #15161e
+color1 #f7768e
+color2 #9ece6a
+color3 #e0af68
+color4 #7aa2f7
+color5 #bb9af7
+color6 #7dcfff
+color7 #a9b1d6
+
+# bright
+color8 #414868
+color9 #f7768e
+color10 #9ece6a
+color11 #e0af68
+color12 #7aa2f7
+color13 #bb9af7
+color14 #7dcfff
+color15 #c0caf5
+color16 #ff9e64
+";
+
+    #[test]
+    fn import_kitty() {
+        let imported = import(KITTY).unwrap();
+        assert_eq!(imported.name.as_deref(), Some("Tokyo Night"));
+
+        let color = imported.colors.get(COLOR_09).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0x41, 0x48, 0x68));
+        assert!(imported.colors.contains_key(URL));
+
+        assert_eq!(
+            imported.report.dropped,
+            vec!["active_border_color", "color16", "cursor"]
+        );
+        assert_eq!(
+            imported.report.derived,
+            vec![(CURSOR.to_string(), FOREGROUND.to_string())]
+        );
+    }
+}
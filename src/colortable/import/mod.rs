@@ -0,0 +1,394 @@
+pub mod alacritty;
+pub mod base16;
+pub mod foot;
+pub mod gogh;
+pub mod iterm;
+pub mod kitty;
+pub mod vscode;
+pub mod wezterm;
+pub mod windows_terminal;
+pub mod xresources;
+
+use super::ColorTable;
+use crate::colortable::rgb::RGB;
+use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
+use crate::config::colorscheme::*;
+use crate::util::value_names;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Required colors that may be missing from a source file, and the
+// colors they are derived from
+const DERIVATIONS: [(&str, &str); 9] = [
+    (CURSOR, FOREGROUND),
+    (COLOR_09, COLOR_01),
+    (COLOR_10, COLOR_02),
+    (COLOR_11, COLOR_03),
+    (COLOR_12, COLOR_04),
+    (COLOR_13, COLOR_05),
+    (COLOR_14, COLOR_06),
+    (COLOR_15, COLOR_07),
+    (COLOR_16, COLOR_08),
+];
+
+// Formats colorschemes can be imported from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    Base16,
+    Iterm,
+    Xresources,
+    Kitty,
+    Alacritty,
+    Foot,
+    Wezterm,
+    WindowsTerminal,
+    Vscode,
+    Gogh,
+}
+
+// Names accepted by `--from`, with their aliases
+impl ValueEnum for Source {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Source::Base16,
+            Source::Iterm,
+            Source::Xresources,
+            Source::Kitty,
+            Source::Alacritty,
+            Source::Foot,
+            Source::Wezterm,
+            Source::WindowsTerminal,
+            Source::Vscode,
+            Source::Gogh,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Source::Base16 => PossibleValue::new("base16").aliases(["base24"]),
+            Source::Iterm => PossibleValue::new("iterm").aliases(["iterm2", "itermcolors"]),
+            Source::Xresources => PossibleValue::new("xresources").aliases(["xrdb", "xdefaults"]),
+            Source::Kitty => PossibleValue::new("kitty"),
+            Source::Alacritty => PossibleValue::new("alacritty"),
+            Source::Foot => PossibleValue::new("foot"),
+            Source::Wezterm => PossibleValue::new("wezterm"),
+            Source::WindowsTerminal => PossibleValue::new("windows-terminal").aliases(["wt"]),
+            Source::Vscode => PossibleValue::new("vscode").aliases(["code"]),
+            Source::Gogh => PossibleValue::new("gogh"),
+        })
+    }
+}
+
+impl Source {
+    // Detects the format of a source file, first from its name, then
+    // from its content
+    pub fn detect(path: &Path, content: &str) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let file_name = path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or_default();
+
+        match (extension, file_name) {
+            ("itermcolors", _) => return Ok(Source::Iterm),
+            ("Xresources" | "xresources" | "Xdefaults", _) | (_, ".Xresources" | ".Xdefaults") => {
+                return Ok(Source::Xresources)
+            }
+            // Alacritty themes have a `colors` table, base16 ones don't
+            ("yaml" | "yml", _) => {
+                return match serde_yaml::from_str::<serde_yaml::Value>(content) {
+                    Ok(yaml) if yaml.get("colors").is_some() => Ok(Source::Alacritty),
+                    _ => Ok(Source::Base16),
+                }
+            }
+            // WezTerm themes define the `ansi`/`brights` arrays
+            ("toml", _) => {
+                return match toml::from_str::<toml::Table>(content) {
+                    Ok(toml) if toml.get("colors").and_then(|c| c.get("ansi")).is_some() => {
+                        Ok(Source::Wezterm)
+                    }
+                    _ => Ok(Source::Alacritty),
+                }
+            }
+            // JSON palettes are told apart by their key names
+            ("json" | "jsonc", _) => {
+                let json = json5::from_str::<serde_json::Value>(content).unwrap_or_default();
+                return if windows_terminal::is_windows_terminal(&json) {
+                    Ok(Source::WindowsTerminal)
+                } else if vscode::is_vscode(&json) {
+                    Ok(Source::Vscode)
+                } else if gogh::is_gogh(&json) {
+                    Ok(Source::Gogh)
+                } else {
+                    Err(format!(
+                        "Can't detect the format of `{}`. Supported JSON files are Windows Terminal, VS Code and Gogh palettes",
+                        path.display()
+                    ))
+                };
+            }
+            _ => {}
+        }
+
+        // Sniffs the content: foot themes are ini files with a [colors]
+        // section, X resources use `name: value`, kitty `name value`
+        let lines: Vec<&str> = content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if lines.contains(&"[colors]") {
+            Ok(Source::Foot)
+        } else if lines.iter().any(|line| {
+            line.split_once(':')
+                .is_some_and(|(resource, _)| resource.trim_end().ends_with("color0"))
+        }) {
+            Ok(Source::Xresources)
+        } else if lines.iter().any(|line| line.starts_with("color0 ")) {
+            Ok(Source::Kitty)
+        } else {
+            Err(format!(
+                "Can't detect the format of `{}`. Use `--from` with one of `{}`",
+                path.display(),
+                value_names::<Source>().join("`, `")
+            ))
+        }
+    }
+
+    // Converts the content of a source file. Some formats may hold
+    // several colorschemes in the same file
+    pub fn import(&self, content: &str) -> Result<Vec<ImportedColorscheme>, String> {
+        let imported = match self {
+            Source::Base16 => base16::import(content),
+            Source::Iterm => iterm::import(content),
+            Source::Xresources => xresources::import(content),
+            Source::Kitty => kitty::import(content),
+            Source::Alacritty => alacritty::import(content),
+            Source::Foot => foot::import(content),
+            Source::Wezterm => wezterm::import(content),
+            Source::WindowsTerminal => return windows_terminal::import(content),
+            Source::Vscode => return vscode::import(content),
+            Source::Gogh => return gogh::import(content),
+        };
+
+        imported.map(|imported| vec![imported])
+    }
+}
+
+// A colorscheme converted from a foreign format
+#[derive(Debug)]
+pub struct ImportedColorscheme {
+    // Name declared inside the imported file, if any
+    pub name: Option<String>,
+    pub colors: ColorTable,
+    pub report: ImportReport,
+}
+
+// What got lost or made up while converting a colorscheme
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    // Keys of the source file without a chromasync counterpart
+    pub dropped: Vec<String>,
+    // Required colors missing from the source file, paired with the
+    // color they were derived from
+    pub derived: Vec<(String, String)>,
+    // Anything else worth a warning, e.g. colors converted from another
+    // color space
+    pub notes: Vec<String>,
+}
+
+// Derives the missing required colors (see DERIVATIONS), validates the
+// colors and builds the ImportedColorscheme
+fn finish_import(
+    name: Option<String>,
+    mut colors: HashMap<String, String>,
+    mut report: ImportReport,
+) -> Result<ImportedColorscheme, String> {
+    for (color, source) in DERIVATIONS {
+        if !colors.contains_key(color) {
+            if let Some(hex) = colors.get(source).cloned() {
+                colors.insert(color.to_string(), hex);
+                report.derived.push((color.to_string(), source.to_string()));
+            }
+        }
+    }
+
+    report.dropped.sort();
+
+    let colors = ColorTable::from_hex_map(colors)?;
+
+    Ok(ImportedColorscheme {
+        name,
+        colors,
+        report,
+    })
+}
+
+// Converts the (key, value) pairs of a source file into chromasync
+// colors. Keys without a counterpart and values that are not colors
+// are reported as dropped
+fn map_json_colors(
+    entries: Vec<(String, serde_json::Value)>,
+    chromasync_name: fn(&str) -> Option<&'static str>,
+) -> (HashMap<String, String>, ImportReport) {
+    let mut colors = HashMap::new();
+    let mut report = ImportReport::default();
+
+    for (key, value) in entries {
+        let hex = match &value {
+            // Unquoted `0xRRGGBB` values of YAML files are integers
+            serde_json::Value::Number(number) => number
+                .as_u64()
+                .filter(|number| *number <= 0xFFFFFF)
+                .and_then(|number| normalize_hex(&format!("{:06x}", number))),
+            value => value.as_str().and_then(normalize_hex),
+        };
+
+        match (chromasync_name(&key), hex) {
+            (Some(name), Some(hex)) => {
+                colors.insert(name.to_string(), hex);
+            }
+            _ => report.dropped.push(key),
+        }
+    }
+
+    (colors, report)
+}
+
+// Normalizes the hex notations found in terminal configs (`#RRGGBB`,
+// `RRGGBB`, `0xRRGGBB` and `#RGB`) into `#RRGGBB`
+fn normalize_hex(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches(['"', '\'']);
+    let hex = value
+        .strip_prefix('#')
+        .or(value.strip_prefix("0x"))
+        .unwrap_or(value);
+
+    let hex = if hex.len() == 3 {
+        hex.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        hex.to_string()
+    };
+
+    RGB::new_from_hex(&hex)
+        .and_then(|rgb| rgb.format(HEX_6_DIGITS_W_HASHTAG))
+        .ok()
+}
+
+// Flattens nested tables and arrays into dotted keys, e.g.
+// `{"colors": {"ansi": ["#000000"]}}` becomes `colors.ansi.0`
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, serde_json::Value)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, out);
+            }
+        }
+        serde_json::Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                flatten(&join(&index.to_string()), value, out);
+            }
+        }
+        _ => out.push((prefix.to_string(), value.clone())),
+    }
+}
+
+// Turns a display name such as "Gruvbox dark, hard" into a name
+// suitable for a colorscheme file, such as "gruvbox-dark-hard"
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn slugify_name() {
+        assert_eq!(slugify("Gruvbox dark, hard"), "gruvbox-dark-hard");
+        assert_eq!(slugify("  Tomorrow Night  "), "tomorrow-night");
+        assert_eq!(slugify("solarized_light"), "solarized-light");
+    }
+
+    #[test]
+    fn normalize_hex_notations() {
+        assert_eq!(normalize_hex("#1d1f21").as_deref(), Some("#1D1F21"));
+        assert_eq!(normalize_hex("1d1f21").as_deref(), Some("#1D1F21"));
+        assert_eq!(normalize_hex("0x1d1f21").as_deref(), Some("#1D1F21"));
+        assert_eq!(normalize_hex("'#abc'").as_deref(), Some("#AABBCC"));
+        assert_eq!(normalize_hex("none"), None);
+    }
+
+    #[test]
+    fn detect_source() {
+        let detect = |path: &str, content: &str| Source::detect(&PathBuf::from(path), content);
+
+        assert_eq!(detect("a.itermcolors", ""), Ok(Source::Iterm));
+        assert_eq!(detect("/home/me/.Xresources", ""), Ok(Source::Xresources));
+        assert_eq!(detect("a.yaml", "base00: \"000000\""), Ok(Source::Base16));
+        assert_eq!(
+            detect("a.yml", "colors:\n  primary: {}"),
+            Ok(Source::Alacritty)
+        );
+        assert_eq!(detect("a.toml", "[colors.primary]"), Ok(Source::Alacritty));
+        assert_eq!(detect("a.toml", "[colors]\nansi = []"), Ok(Source::Wezterm));
+        assert_eq!(
+            detect("a.ini", "[colors]\nregular0=000000"),
+            Ok(Source::Foot)
+        );
+        assert_eq!(detect("a.conf", "color0 #000000"), Ok(Source::Kitty));
+        assert_eq!(detect("-", "*.color0: #000000"), Ok(Source::Xresources));
+        assert_eq!(detect("-", "URxvt*color0: #000000"), Ok(Source::Xresources));
+        assert_eq!(
+            detect("settings.json", r#"{"schemes": []}"#),
+            Ok(Source::WindowsTerminal)
+        );
+        assert_eq!(
+            detect("settings.json", r#"{"workbench.colorCustomizations": {}}"#),
+            Ok(Source::Vscode)
+        );
+        assert_eq!(
+            detect("themes.json", r##"[{"name": "a", "color_01": "#000000"}]"##),
+            Ok(Source::Gogh)
+        );
+        assert!(detect("package.json", r#"{"name": "a"}"#).is_err());
+        assert!(detect("a.txt", "hello").is_err());
+    }
+
+    #[test]
+    fn derive_missing_colors() {
+        let colors: HashMap<String, String> = COLOR_NAMES
+            .iter()
+            .filter(|name| ![CURSOR, COLOR_16].contains(name))
+            .map(|name| (name.to_string(), "#000000".to_string()))
+            .collect();
+
+        let imported = finish_import(None, colors, ImportReport::default()).unwrap();
+        assert_eq!(
+            imported.report.derived,
+            vec![
+                (CURSOR.to_string(), FOREGROUND.to_string()),
+                (COLOR_16.to_string(), COLOR_08.to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// Importer for VS Code terminal colors. Those are the `terminal.*` keys
+// found either in the `workbench.colorCustomizations` of a
+// `settings.json` or in the `colors` of a color theme. Customizations
+// scoped to a theme (`"[Theme Name]": { ... }`) become a colorscheme
+// each, named after the theme
+
+use super::{finish_import, map_json_colors, normalize_hex, ImportedColorscheme};
+use crate::config::colorscheme::*;
+use serde_json::{Map, Value};
+
+const COLOR_CUSTOMIZATIONS: &str = "workbench.colorCustomizations";
+
+// Names of the ANSI colors inside VS Code, without the `terminal.ansi`
+// prefix
+const ANSI_NAMES: [&str; 16] = [
+    "Black",
+    "Red",
+    "Green",
+    "Yellow",
+    "Blue",
+    "Magenta",
+    "Cyan",
+    "White",
+    "BrightBlack",
+    "BrightRed",
+    "BrightGreen",
+    "BrightYellow",
+    "BrightBlue",
+    "BrightMagenta",
+    "BrightCyan",
+    "BrightWhite",
+];
+
+// When the terminal doesn't define its own background/foreground, VS
+// Code uses the editor ones
+const FALLBACKS: [(&str, &str); 2] = [
+    (BACKGROUND, "editor.background"),
+    (FOREGROUND, "editor.foreground"),
+];
+
+// `settings.json` may contain comments and trailing commas, hence it's
+// parsed as JSON5
+pub fn import(content: &str) -> Result<Vec<ImportedColorscheme>, String> {
+    let document: Value = json5::from_str(content).map_err(|e| e.to_string())?;
+
+    let palettes = palettes(&document);
+    if palettes.is_empty() {
+        return Err("No VS Code terminal color found".to_string());
+    }
+
+    palettes
+        .into_iter()
+        .map(|(name, colors)| {
+            import_palette(name.clone(), colors).map_err(|e| match &name {
+                Some(name) => format!("Theme `{}`. {}", name, e),
+                None => e,
+            })
+        })
+        .collect()
+}
+
+// Whether the document looks like a VS Code file
+pub fn is_vscode(document: &Value) -> bool {
+    document.get(COLOR_CUSTOMIZATIONS).is_some() || !palettes(document).is_empty()
+}
+
+// Finds the color maps holding terminal colors, paired with the name
+// of the theme they belong to
+fn palettes(document: &Value) -> Vec<(Option<String>, &Map<String, Value>)> {
+    let has_terminal_colors =
+        |colors: &Map<String, Value>| colors.keys().any(|key| key.starts_with("terminal."));
+    let mut palettes = Vec::new();
+
+    // Color theme
+    if let Some(colors) = document.get("colors").and_then(|c| c.as_object()) {
+        if has_terminal_colors(colors) {
+            let name = document.get("name").and_then(|n| n.as_str());
+            palettes.push((name.map(|n| n.to_string()), colors));
+        }
+    }
+
+    // settings.json
+    if let Some(customizations) = document
+        .get(COLOR_CUSTOMIZATIONS)
+        .and_then(|c| c.as_object())
+    {
+        if has_terminal_colors(customizations) {
+            palettes.push((None, customizations));
+        }
+
+        // Customizations scoped to a theme
+        for (key, value) in customizations {
+            let name = key.strip_prefix('[').and_then(|k| k.strip_suffix(']'));
+            if let (Some(name), Some(colors)) = (name, value.as_object()) {
+                if has_terminal_colors(colors) {
+                    palettes.push((Some(name.to_string()), colors));
+                }
+            }
+        }
+    }
+
+    palettes
+}
+
+fn import_palette(
+    name: Option<String>,
+    palette: &Map<String, Value>,
+) -> Result<ImportedColorscheme, String> {
+    // Only terminal colors are considered, the editor ones are way too
+    // many to be reported as dropped
+    let entries = palette
+        .iter()
+        .filter(|(key, _)| key.starts_with("terminal"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let (mut colors, mut report) = map_json_colors(entries, chromasync_name);
+
+    for (color, fallback) in FALLBACKS {
+        if colors.contains_key(color) {
+            continue;
+        }
+
+        let hex = palette
+            .get(fallback)
+            .and_then(|value| value.as_str())
+            .and_then(normalize_hex);
+
+        if let Some(hex) = hex {
+            colors.insert(color.to_string(), hex);
+            report
+                .derived
+                .push((color.to_string(), fallback.to_string()));
+        }
+    }
+
+    finish_import(name, colors, report)
+}
+
+// Maps a VS Code key onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "terminal.background" => Some(BACKGROUND),
+        "terminal.foreground" => Some(FOREGROUND),
+        "terminalCursor.foreground" => Some(CURSOR),
+        "terminalCursor.background" => Some(CURSOR_TEXT),
+        "terminal.selectionBackground" => Some(SELECTION_BACKGROUND),
+        "terminal.selectionForeground" => Some(SELECTION_FOREGROUND),
+        _ => {
+            let name = key.strip_prefix("terminal.ansi")?;
+            ANSI_NAMES
+                .iter()
+                .position(|ansi_name| *ansi_name == name)
+                .map(|index| ANSI_COLOR_NAMES[index])
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds the terminal.ansi* entries of a palette
+    fn ansi_colors(hex: &str) -> String {
+        ANSI_NAMES
+            .iter()
+            .map(|name| format!(r#""terminal.ansi{}": "{}""#, name, hex))
+            .collect::<Vec<_>>()
+            .join(",\n")
+    }
+
+    #[test]
+    fn import_settings() {
+        let settings = format!(
+            r##"{{
+    "editor.fontSize": 14,
+    // Comments are allowed
+    "workbench.colorCustomizations": {{
+        "[Monokai]": {{
+            "terminal.background": "#272822",
+            "terminal.foreground": "#f8f8f2",
+            "terminalCursor.foreground": "#f8f8f0",
+            "terminal.border": "#414339",
+            {}
+        }},
+        "[Solarized Light]": {{
+            "editor.background": "#fdf6e3",
+            "editor.foreground": "#657b83",
+            {}
+        }},
+    }},
+}}"##,
+            ansi_colors("#75715e"),
+            ansi_colors("#073642")
+        );
+
+        let imported = import(&settings).unwrap();
+        assert_eq!(imported.len(), 2);
+
+        assert_eq!(imported[0].name.as_deref(), Some("Monokai"));
+        assert_eq!(imported[0].report.dropped, vec!["terminal.border"]);
+
+        assert_eq!(imported[1].name.as_deref(), Some("Solarized Light"));
+        let background = imported[1].colors.get(BACKGROUND).unwrap();
+        assert_eq!(
+            (background.0, background.1, background.2),
+            (0xfd, 0xf6, 0xe3)
+        );
+        assert_eq!(
+            imported[1].report.derived,
+            vec![
+                (BACKGROUND.to_string(), "editor.background".to_string()),
+                (FOREGROUND.to_string(), "editor.foreground".to_string()),
+                (CURSOR.to_string(), FOREGROUND.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_color_theme() {
+        let theme = format!(
+            r##"{{
+    "name": "Night Owl",
+    "type": "dark",
+    "colors": {{
+        "editor.background": "#011627",
+        "terminal.background": "#011627",
+        "terminal.foreground": "#d6deeb",
+        {}
+    }}
+}}"##,
+            ansi_colors("#7fdbca")
+        );
+
+        let imported = import(&theme).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name.as_deref(), Some("Night Owl"));
+    }
+
+    #[test]
+    fn no_terminal_colors() {
+        let settings = r##"{"workbench.colorCustomizations": {"editor.background": "#011627"}}"##;
+        assert!(import(settings).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+// Importer for WezTerm TOML color schemes. Colors live inside the
+// `[colors]` table, with the ANSI colors stored in the `ansi` and
+// `brights` arrays. The name is stored in the `[metadata]` table
+
+use super::{finish_import, flatten, map_json_colors, ImportedColorscheme};
+use crate::config::colorscheme::*;
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let document: serde_json::Value = toml::from_str(content).map_err(|e| e.to_string())?;
+
+    let table = document
+        .get("colors")
+        .ok_or("Missing `colors` table".to_string())?;
+
+    let mut entries = Vec::new();
+    flatten("colors", table, &mut entries);
+
+    let (colors, report) = map_json_colors(entries, chromasync_name);
+
+    let name = document
+        .get("metadata")
+        .and_then(|metadata| metadata.get("name"))
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_string());
+
+    finish_import(name, colors, report)
+}
+
+// Maps a flattened WezTerm key onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "colors.background" => Some(BACKGROUND),
+        "colors.foreground" => Some(FOREGROUND),
+        "colors.cursor_bg" => Some(CURSOR),
+        "colors.cursor_fg" => Some(CURSOR_TEXT),
+        "colors.selection_bg" => Some(SELECTION_BACKGROUND),
+        "colors.selection_fg" => Some(SELECTION_FOREGROUND),
+        _ => {
+            let (group, index) = key.strip_prefix("colors.")?.split_once('.')?;
+            let index: usize = index.parse().ok().filter(|index| *index < 8)?;
+
+            match group {
+                "ansi" => Some(ANSI_COLOR_NAMES[index]),
+                "brights" => Some(ANSI_COLOR_NAMES[8 + index]),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEZTERM: &str = r##"
+[colors]
+ansi = ["#0c0c0c", "#c50f1f", "#13a10e", "#c19c00", "#0037da", "#881798", "#3a96dd", "#cccccc"]
+brights = ["#767676", "#e74856", "#16c60c", "#f9f1a5", "#3b78ff", "#b4009e", "#61d6d6", "#f2f2f2"]
+background = "#0c0c0c"
+foreground = "#cccccc"
+cursor_bg = "#ffffff"
+cursor_border = "#ffffff"
+cursor_fg = "#0c0c0c"
+selection_bg = "#ffffff"
+selection_fg = "#0c0c0c"
+
+[metadata]
+name = "Campbell"
+origin_url = "https://github.com/microsoft/terminal"
+"##;
+
+    #[test]
+    fn import_wezterm() {
+        let imported = import(WEZTERM).unwrap();
+        assert_eq!(imported.name.as_deref(), Some("Campbell"));
+
+        let color = imported.colors.get(COLOR_09).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0x76, 0x76, 0x76));
+        let color = imported.colors.get(COLOR_08).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0xcc, 0xcc, 0xcc));
+
+        assert_eq!(imported.report.dropped, vec!["colors.cursor_border"]);
+        assert!(imported.report.derived.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+// Importer for Windows Terminal color schemes. A file may hold a single
+// scheme object, an array of schemes or a whole `settings.json` with
+// its `schemes` array. Each scheme becomes a chromasync colorscheme
+
+use super::{finish_import, map_json_colors, ImportedColorscheme};
+use crate::config::colorscheme::*;
+use serde_json::Value;
+
+// Names of the ANSI colors inside Windows Terminal schemes
+const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "purple",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightPurple",
+    "brightCyan",
+    "brightWhite",
+];
+
+// `settings.json` may contain comments and trailing commas, hence it's
+// parsed as JSON5
+pub fn import(content: &str) -> Result<Vec<ImportedColorscheme>, String> {
+    let document: Value = json5::from_str(content).map_err(|e| e.to_string())?;
+
+    // An empty `schemes` array is still recognized, but there's nothing
+    // to import
+    schemes(&document)
+        .filter(|schemes| !schemes.is_empty())
+        .ok_or("No Windows Terminal color scheme found".to_string())?
+        .iter()
+        .enumerate()
+        .map(|(index, scheme)| {
+            import_scheme(scheme).map_err(|e| format!("Scheme #{}. {}", index + 1, e))
+        })
+        .collect()
+}
+
+// Whether the document looks like a Windows Terminal file
+pub fn is_windows_terminal(document: &Value) -> bool {
+    schemes(document).is_some()
+}
+
+// Finds the schemes inside the document
+fn schemes(document: &Value) -> Option<Vec<&Value>> {
+    let is_scheme = |value: &Value| value.get("brightBlack").is_some();
+
+    match document {
+        Value::Object(object) if object.contains_key("schemes") => {
+            Some(object["schemes"].as_array()?.iter().collect())
+        }
+        Value::Object(_) if is_scheme(document) => Some(vec![document]),
+        Value::Array(array) if !array.is_empty() && array.iter().all(is_scheme) => {
+            Some(array.iter().collect())
+        }
+        _ => None,
+    }
+}
+
+fn import_scheme(scheme: &Value) -> Result<ImportedColorscheme, String> {
+    let scheme = scheme
+        .as_object()
+        .ok_or("The scheme is not an object".to_string())?;
+
+    let name = scheme
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_string());
+
+    let entries = scheme
+        .iter()
+        .filter(|(key, _)| *key != "name")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let (colors, report) = map_json_colors(entries, chromasync_name);
+
+    finish_import(name, colors, report)
+}
+
+// Maps a Windows Terminal key onto a chromasync color
+fn chromasync_name(key: &str) -> Option<&'static str> {
+    match key {
+        "background" => Some(BACKGROUND),
+        "foreground" => Some(FOREGROUND),
+        "cursorColor" => Some(CURSOR),
+        "selectionBackground" => Some(SELECTION_BACKGROUND),
+        _ => ANSI_NAMES
+            .iter()
+            .position(|name| *name == key)
+            .map(|index| ANSI_COLOR_NAMES[index]),
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r##"{
+    // Windows Terminal settings may contain comments
+    "defaultProfile": "{61c54bbd-c2c6-5271-96e7-009a87ff44bf}",
+    "schemes": [
+        {
+            "name": "Campbell",
+            "background": "#0C0C0C",
+            "foreground": "#CCCCCC",
+            "cursorColor": "#FFFFFF",
+            "selectionBackground": "#FFFFFF",
+            "black": "#0C0C0C",
+            "red": "#C50F1F",
+            "green": "#13A10E",
+            "yellow": "#C19C00",
+            "blue": "#0037DA",
+            "purple": "#881798",
+            "cyan": "#3A96DD",
+            "white": "#CCCCCC",
+            "brightBlack": "#767676",
+            "brightRed": "#E74856",
+            "brightGreen": "#16C60C",
+            "brightYellow": "#F9F1A5",
+            "brightBlue": "#3B78FF",
+            "brightPurple": "#B4009E",
+            "brightCyan": "#61D6D6",
+            "brightWhite": "#F2F2F2"
+        },
+        {
+            "name": "One Half Dark",
+            "background": "#282C34",
+            "foreground": "#DCDFE4",
+            "black": "#282C34",
+            "red": "#E06C75",
+            "green": "#98C379",
+            "yellow": "#E5C07B",
+            "blue": "#61AFEF",
+            "purple": "#C678DD",
+            "cyan": "#56B6C2",
+            "white": "#DCDFE4",
+            "brightBlack": "#5A6374",
+            "brightRed": "#E06C75",
+            "brightGreen": "#98C379",
+            "brightYellow": "#E5C07B",
+            "brightBlue": "#61AFEF",
+            "brightPurple": "#C678DD",
+            "brightCyan": "#56B6C2",
+            "brightWhite": "#DCDFE4",
+        },
+    ],
+}"##;
+
+    #[test]
+    fn import_settings() {
+        let imported = import(SETTINGS).unwrap();
+        assert_eq!(imported.len(), 2);
+
+        assert_eq!(imported[0].name.as_deref(), Some("Campbell"));
+        let color = imported[0].colors.get(COLOR_06).unwrap();
+        assert_eq!((color.0, color.1, color.2), (0x88, 0x17, 0x98));
+        assert!(imported[0].colors.contains_key(SELECTION_BACKGROUND));
+
+        // The cursor color is derived from the foreground
+        assert_eq!(imported[1].name.as_deref(), Some("One Half Dark"));
+        assert_eq!(
+            imported[1].report.derived,
+            vec![(CURSOR.to_string(), FOREGROUND.to_string())]
+        );
+    }
+
+    #[test]
+    fn import_single_scheme() {
+        let settings: Value = json5::from_str(SETTINGS).unwrap();
+        let scheme = serde_json::to_string(&settings["schemes"][0]).unwrap();
+
+        let imported = import(&scheme).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name.as_deref(), Some("Campbell"));
+    }
+
+    #[test]
+    fn invalid_scheme() {
+        let settings = SETTINGS.replace(r##""red": "#E06C75","##, "");
+        let error = import(&settings).unwrap_err();
+        assert!(error.starts_with("Scheme #2."), "{}", error);
+    }
+
+    #[test]
+    fn empty_schemes() {
+        assert!(is_windows_terminal(
+            &json5::from_str(r#"{"schemes": []}"#).unwrap()
+        ));
+        assert!(import(r#"{"schemes": []}"#).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+// Importer for X resources, either from an `~/.Xresources` file or from
+// the output of `xrdb -query`. Resources may be prefixed by a class
+// and/or by wildcards (`*.color0`, `URxvt*background`, ...), colors
+// may use the X11 `rgb:rr/gg/bb` notation and values may refer to
+// C-preprocessor-style `#define` macros
+
+use super::{ImportReport, ImportedColorscheme};
+use crate::colortable::rgb::RGB;
+use crate::colortable::ColorTable;
+use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
+use crate::config::colorscheme::*;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+// Matches `#define NAME VALUE`
+static REGEX_DEFINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*#\s*define\s+(\w+)\s+(.*?)\s*$").unwrap());
+
+// Matches the words that may be replaced by a macro
+static REGEX_WORD: Lazy<Regex> = Lazy::new(|| Regex::new(r"\w+").unwrap());
+
+// Matches `/* ... */` comments
+static REGEX_C_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"/\*.*?\*/").unwrap());
+
+// Macros referring to other macros are expanded up to this depth
+const MAX_MACRO_EXPANSIONS: usize = 16;
+
+// X resource names and their chromasync counterparts
+const XRESOURCES_MAPPING: [(&str, &str); 20] = [
+    ("background", BACKGROUND),
+    ("foreground", FOREGROUND),
+    ("cursorColor", CURSOR),
+    ("color0", COLOR_01),
+    ("color1", COLOR_02),
+    ("color2", COLOR_03),
+    ("color3", COLOR_04),
+    ("color4", COLOR_05),
+    ("color5", COLOR_06),
+    ("color6", COLOR_07),
+    ("color7", COLOR_08),
+    ("color8", COLOR_09),
+    ("color9", COLOR_10),
+    ("color10", COLOR_11),
+    ("color11", COLOR_12),
+    ("color12", COLOR_13),
+    ("color13", COLOR_14),
+    ("color14", COLOR_15),
+    ("color15", COLOR_16),
+    // Optional colors
+    ("colorBD", BOLD),
+];
+
+pub fn import(content: &str) -> Result<ImportedColorscheme, String> {
+    let mut macros: HashMap<String, String> = HashMap::new();
+
+    // Resources are split in generic ones (`*.color0`, `color0`) and
+    // class-specific ones (`URxvt.color0`). Generic resources take
+    // precedence, class-specific ones are used as a fallback
+    let mut generic: HashMap<&str, String> = HashMap::new();
+    let mut specific: HashMap<&str, String> = HashMap::new();
+    let mut report = ImportReport::default();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = REGEX_C_COMMENT.replace_all(line, "");
+        let line = line.trim();
+
+        // Skips empty lines and comments
+        if line.is_empty() || line.starts_with('!') || line.starts_with("//") {
+            continue;
+        }
+
+        // Stores macros
+        if let Some(caps) = REGEX_DEFINE.captures(line) {
+            let value = expand_macros(&caps[2], &macros);
+            macros.insert(caps[1].to_string(), value);
+            continue;
+        }
+
+        // Skips other preprocessor directives (#include, #ifdef, ...)
+        if line.starts_with('#') {
+            continue;
+        }
+
+        // Splits `resource: value`. Anything else (e.g. the continuation
+        // of a multi-line value) is skipped
+        let Some((resource, value)) = line.split_once(':') else {
+            report.notes.push(format!(
+                "Line {}: skipped ill formed resource `{}`",
+                index + 1,
+                line
+            ));
+            continue;
+        };
+        let resource = expand_macros(resource.trim(), &macros);
+        let value = expand_macros(value.trim(), &macros);
+
+        // The resource name is the last component, the rest is a prefix
+        // made of classes and wildcards
+        let split_at = resource.rfind(['.', '*']).map_or(0, |i| i + 1);
+        let (prefix, resource_name) = resource.split_at(split_at);
+
+        let name = XRESOURCES_MAPPING
+            .iter()
+            .find(|(xresource_name, _)| *xresource_name == resource_name)
+            .map(|(_, name)| *name);
+
+        if let Some(name) = name {
+            let hex = hex_from_xcolor(&value)
+                .map_err(|e| format!("Line {}: resource `{}`. {}", index + 1, resource, e))?;
+
+            if prefix.chars().all(|c| c == '*' || c == '.') {
+                generic.insert(name, hex);
+            } else {
+                specific.entry(name).or_insert(hex);
+            }
+        }
+    }
+
+    for (name, hex) in specific {
+        generic.entry(name).or_insert(hex);
+    }
+
+    let colors = ColorTable::from_hex_map(
+        generic
+            .into_iter()
+            .map(|(name, hex)| (name.to_string(), hex)),
+    )?;
+
+    // X resources don't store the name of the colorscheme
+    Ok(ImportedColorscheme {
+        name: None,
+        colors,
+        report,
+    })
+}
+
+// Replaces every macro occurring in text with its value
+fn expand_macros(text: &str, macros: &HashMap<String, String>) -> String {
+    let mut text = text.to_string();
+
+    for _ in 0..MAX_MACRO_EXPANSIONS {
+        let expanded = REGEX_WORD
+            .replace_all(&text, |caps: &Captures| {
+                macros.get(&caps[0]).cloned().unwrap_or(caps[0].to_string())
+            })
+            .to_string();
+
+        if expanded == text {
+            break;
+        }
+        text = expanded;
+    }
+
+    text
+}
+
+// Converts an X11 color into an hex string. Supported notations are
+// `#RGB` (up to 4 digits per component) and `rgb:R/G/B` (1 to 4 digits
+// per component)
+fn hex_from_xcolor(color: &str) -> Result<String, String> {
+    let rgb = if let Some(hex) = color.strip_prefix('#') {
+        // Each component takes the same number of digits. Components
+        // with less than 2 digits are the most significant bits
+        let digits = hex.len() / 3;
+        if hex.len() % 3 != 0 || !(1..=4).contains(&digits) {
+            return Err(format!("Invalid color `{}`", color));
+        }
+
+        let component = |i: usize| -> Result<u8, String> {
+            let value = u16::from_str_radix(&hex[i * digits..(i + 1) * digits], 16)
+                .map_err(|_| format!("Invalid color `{}`", color))?;
+            Ok(match digits {
+                1 => (value << 4) as u8,
+                2 => value as u8,
+                _ => (value >> ((digits - 2) * 4)) as u8,
+            })
+        };
+
+        RGB(component(0)?, component(1)?, component(2)?)
+    } else if let Some(components) = color.strip_prefix("rgb:") {
+        // Each component is scaled from its own number of digits
+        let components: Vec<&str> = components.split('/').collect();
+        if components.len() != 3 {
+            return Err(format!("Invalid color `{}`", color));
+        }
+
+        let component = |c: &str| -> Result<u8, String> {
+            if !(1..=4).contains(&c.len()) {
+                return Err(format!("Invalid color `{}`", color));
+            }
+            let value =
+                u32::from_str_radix(c, 16).map_err(|_| format!("Invalid color `{}`", color))?;
+            let max = (1_u32 << (4 * c.len())) - 1;
+            Ok(((value * 255) as f32 / max as f32).round() as u8)
+        };
+
+        RGB(
+            component(components[0])?,
+            component(components[1])?,
+            component(components[2])?,
+        )
+    } else {
+        return Err(format!(
+            "Unsupported color `{}`. Use either `#RRGGBB` or `rgb:RR/GG/BB`",
+            color
+        ));
+    };
+
+    rgb.format(HEX_6_DIGITS_W_HASHTAG)
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XRESOURCES: &str = r#"
+! Colors
+#define base00 #1d1f21
+#define base08 rgb:cc/66/66
+#define red base08
+
+*.foreground:   #c5c8c6
+*.background:   base00
+*.cursorColor:  #c5c8c6
+URxvt*background: #000000
+
+*.color0:       base00
+*.color8:       #969896
+*.color1:       red
+*.color9:       rgb:f/6/6
+*color2:        #b5bd68
+*color10:       #b5bd68 /* green */
+*.color3:       #f0c674
+*.color11:      #f0c674
+*.color4:       #81a2be
+*.color12:      #81a2be
+*.color5:       #b294bb
+*.color13:      #b294bb
+*.color6:       #8abeb7
+*.color14:      #8abeb7
+*.color7:       #c5c8c6
+*.color15:      #ffffff
+"#;
+
+    // Output of `xrdb -query`
+    const XRDB_QUERY: &str = "*background:\t#1d1f21\n*color0:\t#1d1f21\n*color1:\t#cc6666\n*color10:\t#b5bd68\n*color11:\t#f0c674\n*color12:\t#81a2be\n*color13:\t#b294bb\n*color14:\t#8abeb7\n*color15:\t#ffffff\n*color2:\t#b5bd68\n*color3:\t#f0c674\n*color4:\t#81a2be\n*color5:\t#b294bb\n*color6:\t#8abeb7\n*color7:\t#c5c8c6\n*color8:\t#969896\n*color9:\t#cc6666\n*cursorColor:\t#c5c8c6\n*foreground:\t#c5c8c6\nURxvt.font:\txft:monospace:size=10\nXft.dpi:\t96\n";
+
+    fn assert_color(colors: &ColorTable, name: &str, expected: (u8, u8, u8)) {
+        let color = colors.get(name).unwrap();
+        assert_eq!((color.0, color.1, color.2), expected, "Color `{}`", name);
+    }
+
+    #[test]
+    fn import_xresources() {
+        let colors = import(XRESOURCES).unwrap().colors;
+
+        // Macros, nested macros and generic resources precedence
+        assert_color(&colors, BACKGROUND, (0x1d, 0x1f, 0x21));
+        assert_color(&colors, COLOR_01, (0x1d, 0x1f, 0x21));
+        assert_color(&colors, COLOR_02, (0xcc, 0x66, 0x66));
+
+        // Single digit rgb: components are scaled
+        assert_color(&colors, COLOR_10, (0xff, 0x66, 0x66));
+
+        // Trailing C comments are stripped
+        assert_color(&colors, COLOR_11, (0xb5, 0xbd, 0x68));
+    }
+
+    #[test]
+    fn import_xrdb_query() {
+        let colors = import(XRDB_QUERY).unwrap().colors;
+        assert_color(&colors, FOREGROUND, (0xc5, 0xc8, 0xc6));
+        assert_color(&colors, COLOR_16, (0xff, 0xff, 0xff));
+    }
+
+    #[test]
+    fn ill_formed_lines() {
+        // A resource without value, then the continuation of a value
+        let content = format!("{}URxvt.geometry\n  80x24\n", XRDB_QUERY);
+        let imported = import(&content).unwrap();
+        assert_color(&imported.colors, COLOR_16, (0xff, 0xff, 0xff));
+        assert_eq!(
+            imported.report.notes,
+            [
+                "Line 22: skipped ill formed resource `URxvt.geometry`",
+                "Line 23: skipped ill formed resource `80x24`"
+            ]
+        );
+    }
+
+    #[test]
+    fn class_specific_fallback() {
+        let content = XRDB_QUERY.replace("*background", "URxvt.background");
+        let colors = import(&content).unwrap().colors;
+        assert_color(&colors, BACKGROUND, (0x1d, 0x1f, 0x21));
+    }
+
+    #[test]
+    fn xcolor_notations() {
+        assert_eq!(hex_from_xcolor("#fff").unwrap(), "#F0F0F0");
+        assert_eq!(hex_from_xcolor("#123456").unwrap(), "#123456");
+        assert_eq!(hex_from_xcolor("#ffff80800000").unwrap(), "#FF8000");
+        assert_eq!(hex_from_xcolor("rgb:ffff/8080/0").unwrap(), "#FF8000");
+        assert!(hex_from_xcolor("rgb:ff/80").is_err());
+        assert!(hex_from_xcolor("#12345").is_err());
+        assert!(hex_from_xcolor("red").is_err());
+    }
+
+    #[test]
+    fn missing_color() {
+        let content = XRDB_QUERY.replace("*color15:\t#ffffff\n", "");
+        assert!(import(&content).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+// Optional metadata of a colorscheme, stored in the `meta` section:
+//
+//     [meta]
+//     name = "Gruvbox Dark"
+//     author = "Pavel Pertsev"
+//     variant = "dark"
+//     tags = ["retro", "warm"]
+//
+// None of the fields is required. Metadata isn't inherited through
+// `extends`, since it describes a single colorscheme file.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Meta {
+    // Display name, e.g. `Gruvbox Dark`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    // Whether the colorscheme is dark or light. When missing, it's
+    // guessed from the background luminance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<Variant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+impl Meta {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Overrides the fields defined by `other`, e.g. the meta section of a
+    // variant over the shared one
+    pub fn merge(&mut self, other: Meta) {
+        self.name = other.name.or(self.name.take());
+        self.author = other.author.or(self.author.take());
+        self.variant = other.variant.or(self.variant);
+        self.description = other.description.or(self.description.take());
+        self.license = other.license.or(self.license.take());
+        if !other.tags.is_empty() {
+            self.tags = other.tags;
+        }
+    }
+
+    // Whether the colorscheme is tagged with `tag` (case insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    // JSON Schema of the `meta` section (see colortable::schema)
+    pub fn json_schema() -> Value {
+        let variants: Vec<String> = [Variant::Dark, Variant::Light]
+            .iter()
+            .map(Variant::to_string)
+            .collect();
+
+        json!({
+            "description": "Optional metadata of the colorscheme",
+            "type": "object",
+            "properties": {
+                "name": { "description": "Display name", "type": "string" },
+                "author": { "type": "string" },
+                "variant": {
+                    "description": "Whether the colorscheme is dark or light. When missing, it's guessed from the background luminance",
+                    "enum": variants,
+                },
+                "description": { "type": "string" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "license": { "type": "string" },
+            },
+            "additionalProperties": false,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    Dark,
+    Light,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dark => write!(f, "dark"),
+            Self::Light => write!(f, "light"),
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_meta() {
+        let meta: Meta = toml::from_str(
+            r#"
+            name = "Gruvbox Dark"
+            variant = "dark"
+            tags = ["Retro", "warm"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(meta.name.as_deref(), Some("Gruvbox Dark"));
+        assert_eq!(meta.variant, Some(Variant::Dark));
+        assert!(meta.has_tag("retro"));
+        assert!(!meta.has_tag("cold"));
+        assert!(meta.author.is_none());
+    }
+
+    #[test]
+    fn invalid_meta() {
+        assert!(toml::from_str::<Meta>(r#"variant = "dim""#).is_err());
+        assert!(toml::from_str::<Meta>(r#"homepage = "example.com""#).is_err());
+    }
+
+    #[test]
+    fn json_schema() {
+        let meta = Meta {
+            name: Some(String::new()),
+            author: Some(String::new()),
+            variant: Some(Variant::Dark),
+            description: Some(String::new()),
+            tags: vec![String::new()],
+            license: Some(String::new()),
+        };
+
+        // The schema describes every field
+        let fields = serde_json::to_value(meta).unwrap();
+        let schema = Meta::json_schema();
+        let mut expected: Vec<&String> = fields.as_object().unwrap().keys().collect();
+        let mut properties: Vec<&String> =
+            schema["properties"].as_object().unwrap().keys().collect();
+        expected.sort();
+        properties.sort();
+        assert_eq!(properties, expected);
+        assert_eq!(
+            schema["properties"]["variant"]["enum"],
+            json!(["dark", "light"])
+        );
+    }
+}
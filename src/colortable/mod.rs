@@ -1,58 +1,466 @@
+pub mod color_format;
+pub mod css;
+pub mod export;
+mod expression;
+pub mod format;
+pub mod generate;
+pub mod import;
+pub mod meta;
 pub mod rgb;
+pub mod rgba;
+pub mod schema;
+mod serializer;
 mod visitor;
 
 use crate::config::blueprint::MIXED_COLOR_FIELD_SEPARATOR;
-use crate::util::read_file;
-use rgb::RGB;
-use std::collections::HashMap;
+use crate::config::colorscheme::{
+    BACKGROUND, BASE_COLOR_NAMES, COLOR_NAMES, EXTENDS, EXTRA_SECTION, META_SECTION,
+    OPTIONAL_COLOR_DEFAULTS, OPTIONAL_COLOR_NAMES, VARIANTS_SECTION,
+};
+use crate::util::{read_file, search_colorscheme, search_colorscheme_all};
+use expression::Expression;
+use format::Format;
+use meta::{Meta, Variant};
+use rgb::MixSpace;
+use rgba::RGBA;
+use serde::de::value::{Error as ValueError, MapDeserializer};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Color table that holds all the colors of the colorscheme plus
 // OS-specific colors and mixed colors (weighted average of two colors)
 #[derive(Debug)]
-pub struct ColorTable(HashMap<String, RGB>);
+pub struct ColorTable {
+    colors: HashMap<String, RGBA>,
+    // Optional colors that were missing from the colorscheme and were
+    // derived from the other colors
+    derived: HashSet<String>,
+    // Parent colorscheme, as declared by the `extends` key. It's
+    // resolved (and reset) by from_file_path
+    extends: Option<String>,
+    // File each color was read from, when loaded through from_file_path
+    origins: HashMap<String, PathBuf>,
+    // Colors defined through an expression (e.g. `lighten(color_01,
+    // 15%)`) that haven't been evaluated yet
+    expressions: HashMap<String, Expression>,
+    // Expressions of the colors evaluated so far, inherited as such by
+    // the colorschemes that extend this one
+    evaluated: HashMap<String, Expression>,
+    // Content of the meta section
+    meta: Meta,
+    // Variants of the variants section that haven't been chosen yet
+    variants: Vec<(String, ColorTable)>,
+    // Names of the variants of the colorscheme, in the same order as the
+    // file, and the chosen one (see apply_variant)
+    variant_names: Vec<String>,
+    variant_name: Option<String>,
+}
 
 impl ColorTable {
     pub fn with_capacity(capacity: usize) -> ColorTable {
-        ColorTable(HashMap::with_capacity(capacity))
+        ColorTable {
+            colors: HashMap::with_capacity(capacity),
+            derived: HashSet::new(),
+            extends: None,
+            origins: HashMap::new(),
+            expressions: HashMap::new(),
+            evaluated: HashMap::new(),
+            meta: Meta::default(),
+            variants: Vec::new(),
+            variant_names: Vec::new(),
+            variant_name: None,
+        }
     }
 
+    // Loads a colorscheme file. When the colorscheme extends another
+    // one, the whole parent chain is resolved from COLORSCHEMES_DIR and
+    // merged. Colorschemes with variants use the first one
     pub fn from_file_path(path: &PathBuf) -> Result<Self, String> {
+        Self::from_file_path_with_variant(path, None)
+    }
+
+    // Loads a colorscheme file using one of its variants (e.g. `light`).
+    // The variant is looked up along the whole `extends` chain
+    pub fn from_file_path_with_variant(
+        path: &PathBuf,
+        variant: Option<&str>,
+    ) -> Result<Self, String> {
+        let colors = Self::_from_file_path(path, variant, &mut Vec::new())?;
+
+        match (variant, &colors.variant_name) {
+            (Some(variant), None) => Err(format!(
+                "Can't use variant `{}`. Colorscheme `{}` has no variants",
+                variant,
+                path.display()
+            )),
+            _ => Ok(colors),
+        }
+    }
+
+    // `chain` holds the files that extend the current one, so that
+    // cycles can be detected
+    fn _from_file_path(
+        path: &PathBuf,
+        variant: Option<&str>,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<Self, String> {
         // Checks if the colorscheme file exists
         if !path.exists() {
             return Err(format!("Can't find colorscheme `{}`.", path.display()));
         }
 
+        // Checks whether the file was already visited
+        let canonical_path = fs::canonicalize(path).unwrap_or(path.clone());
+        if chain.contains(&canonical_path) {
+            chain.push(canonical_path);
+            return Err(format!(
+                "Cyclic `{}` chain: `{}`",
+                EXTENDS,
+                chain
+                    .iter()
+                    .map(|p| p.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("` -> `")
+            ));
+        }
+
+        // Detects the file format from its extension
+        let format = Format::from_path(path)?;
+
         // Reads the file content
-        let json_str = read_file(&path)?;
+        let content = read_file(path)?;
+
+        // Parses the colorscheme. Errors in a parent colorscheme report
+        // the file they come from
+        let in_file = |e: String| match chain.is_empty() {
+            true => e,
+            false => format!("In `{}`. {}", path.display(), e),
+        };
+        let mut colors: Self = format.parse(&content).map_err(in_file)?;
+
+        // Chooses the variant, which is then completed like a colorscheme
+        // that extends another one
+        let has_variants = !colors.variants.is_empty();
+        colors.apply_variant(variant).map_err(in_file)?;
+
+        colors.origins = colors
+            .colors
+            .keys()
+            .chain(colors.expressions.keys())
+            .filter(|name| !colors.derived.contains(*name))
+            .map(|name| (name.clone(), path.clone()))
+            .collect();
+
+        // Merges the parent colorscheme
+        if let Some(parent_name) = colors.extends.take() {
+            // A colorscheme can extend the one it shadows (e.g. a user
+            // `gruvbox` tweaking the system `gruvbox`)
+            let parent_path = search_colorscheme_all(&parent_name)
+                .map(|paths| {
+                    paths.into_iter().find(|parent_path| {
+                        fs::canonicalize(parent_path).unwrap_or(parent_path.clone())
+                            != canonical_path
+                    })
+                })
+                .transpose()
+                .unwrap_or_else(|| search_colorscheme(&parent_name))
+                .map_err(|e| format!("Can't resolve the parent of `{}`. {}", path.display(), e))?;
+
+            chain.push(canonical_path);
+            let variant = colors.variant_name.as_deref().or(variant);
+            let parent = Self::_from_file_path(&parent_path, variant, chain)?;
+
+            colors.inherit(parent);
+            colors.complete()?;
+        } else if has_variants {
+            colors.complete().map_err(in_file)?;
+        }
+
+        Ok(colors)
+    }
+
+    // Writes the colorscheme to a file. The file format is detected
+    // from its extension
+    pub fn to_file_path(&self, path: &PathBuf) -> Result<(), String> {
+        let format = Format::from_path(path)?;
+
+        let mut content = format.serialize(self)?;
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    // Builds a ColorTable out of (color name, hex color) pairs. The pairs
+    // go through the same validation as colorscheme files
+    pub fn from_hex_map<I>(entries: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let deserializer = MapDeserializer::<_, ValueError>::new(entries.into_iter());
+
+        ColorTable::deserialize(deserializer).map_err(|e| e.to_string())
+    }
+
+    // Merges the colors of a variant over the colors shared by all the
+    // variants. Without a name, the first variant is used
+    fn apply_variant(&mut self, name: Option<&str>) -> Result<(), String> {
+        if self.variants.is_empty() {
+            return Ok(());
+        }
+
+        let variants = std::mem::take(&mut self.variants);
+        self.variant_names = variants.iter().map(|(name, _)| name.clone()).collect();
+
+        let (variant_name, variant) = match name {
+            Some(name) => variants
+                .into_iter()
+                .find(|(variant_name, _)| variant_name == name)
+                .ok_or(format!(
+                    "Unknown variant `{}`. Available variants are `{}`",
+                    name,
+                    self.variant_names.join("`, `")
+                ))?,
+            None => variants.into_iter().next().unwrap(),
+        };
+
+        // A variant can override the shared colors and metadata
+        self.meta.merge(variant.meta);
+        for (name, color) in variant.colors {
+            self.expressions.remove(&name);
+            self.colors.insert(name, color);
+        }
+        for (name, expression) in variant.expressions {
+            self.colors.remove(&name);
+            self.expressions.insert(name, expression);
+        }
+        self.variant_name = Some(variant_name);
+
+        Ok(())
+    }
+
+    // Adds the colors of the parent colorscheme that aren't overridden.
+    // The parent's expressions are inherited unevaluated, so that they
+    // refer to the merged colors (e.g. `lighten(color_01, 15%)` follows an
+    // overridden `color_01`). The optional colors derived by the parent
+    // aren't inherited: they're derived again from the merged colors. So
+    // are the variants, when the colorscheme doesn't define its own
+    fn inherit(&mut self, parent: ColorTable) {
+        if self.variant_names.is_empty() {
+            self.variant_names = parent.variant_names;
+            self.variant_name = parent.variant_name;
+        }
+
+        for (name, expression) in parent.evaluated.into_iter().chain(parent.expressions) {
+            if self.contains_key(&name) || self.expressions.contains_key(&name) {
+                continue;
+            }
+
+            if let Some(origin) = parent.origins.get(&name) {
+                self.origins.insert(name.clone(), origin.clone());
+            }
+            self.expressions.insert(name, expression);
+        }
+
+        for (name, color) in parent.colors {
+            if self.contains_key(&name)
+                || self.expressions.contains_key(&name)
+                || parent.derived.contains(&name)
+            {
+                continue;
+            }
 
-        // Parses the colorscheme
-        serde_json::from_str(&json_str).map_err(|e| e.to_string())
+            if let Some(origin) = parent.origins.get(&name) {
+                self.origins.insert(name.clone(), origin.clone());
+            }
+            self.colors.insert(name, color);
+        }
+    }
+
+    // Evaluates the expressions, checks that all the required colors are
+    // defined, then fills in the missing optional ones
+    fn complete(&mut self) -> Result<(), String> {
+        let mut names: Vec<String> = self.expressions.keys().cloned().collect();
+        names.sort();
+        for name in names {
+            self.evaluate_expression(&name, &mut Vec::new())?;
+        }
+
+        if self.len() < COLOR_NAMES.len() {
+            for &color_name in &COLOR_NAMES {
+                if !self.contains_key(color_name) {
+                    return Err(format!("Missing required color `{}`", color_name));
+                }
+            }
+        }
+
+        self.derive_optional_colors();
+
+        Ok(())
+    }
+
+    // Value of a color, evaluating its expression if needed. `chain`
+    // holds the colors whose evaluation is in progress, so that cyclic
+    // references can be detected
+    fn evaluate_expression(&mut self, name: &str, chain: &mut Vec<String>) -> Result<RGBA, String> {
+        if let Some(color) = self.colors.get(name) {
+            return Ok(color.clone());
+        }
+
+        if chain.iter().any(|n| n == name) {
+            chain.push(name.to_string());
+            return Err(format!(
+                "Cyclic color references: `{}`",
+                chain.join("` -> `")
+            ));
+        }
+
+        let expression = self
+            .expressions
+            .remove(name)
+            .ok_or(format!("Unknown color `{}`", name))?;
+
+        chain.push(name.to_string());
+        let color = expression.evaluate(&mut |reference, column| {
+            let known = self.colors.contains_key(reference)
+                || self.expressions.contains_key(reference)
+                || chain.iter().any(|n| n == reference);
+            // Names that aren't part of the colorscheme can be CSS
+            // named colors, e.g. `rebeccapurple`
+            if !known {
+                return css::named_color(reference).ok_or(format!(
+                    "Unknown color `{}` in the value of `{}` (column {}). It's neither a color of the colorscheme nor a CSS named color",
+                    reference, name, column
+                ));
+            }
+
+            self.evaluate_expression(reference, chain)
+        })?;
+        chain.pop();
+
+        self.colors.insert(name.to_string(), color.clone());
+        self.evaluated.insert(name.to_string(), expression);
+
+        Ok(color)
+    }
+
+    // File a color was read from. Colors inherited through `extends`
+    // come from the parent colorschemes
+    pub fn origin(&self, name: &str) -> Option<&Path> {
+        self.origins.get(name).map(|path| path.as_path())
     }
 
-    pub fn get_composite(&mut self, color1: &String, amount: u8, color2: &String) -> Option<&RGB> {
-        let name = format!(
-            "{}{}{}{}{}",
-            color1, MIXED_COLOR_FIELD_SEPARATOR, amount, MIXED_COLOR_FIELD_SEPARATOR, color2
-        );
+    // Computes the optional colors missing from the colorscheme, as
+    // described by OPTIONAL_COLOR_DEFAULTS
+    pub fn derive_optional_colors(&mut self) {
+        for (name, color1, amount, color2) in OPTIONAL_COLOR_DEFAULTS {
+            if self.contains_key(name) {
+                continue;
+            }
 
-        if self.0.contains_key(&name) {
+            if let Some(color) = self._make_composite(
+                &color1.to_string(),
+                amount,
+                &color2.to_string(),
+                MixSpace::Srgb,
+            ) {
+                self.colors.insert(name.to_string(), color);
+                self.derived.insert(name.to_string());
+            }
+        }
+    }
+
+    // Whether a color was derived instead of being defined by the
+    // colorscheme
+    pub fn is_derived(&self, name: &str) -> bool {
+        self.derived.contains(name)
+    }
+
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    pub fn set_meta(&mut self, meta: Meta) {
+        self.meta = meta;
+    }
+
+    // Names of the variants of the colorscheme, empty if it has none
+    pub fn variant_names(&self) -> &[String] {
+        &self.variant_names
+    }
+
+    // Name of the chosen variant
+    pub fn variant_name(&self) -> Option<&str> {
+        self.variant_name.as_deref()
+    }
+
+    // Dark or light, as declared by the name of the chosen variant or by
+    // the meta section, or guessed from the background luminance
+    pub fn variant(&self) -> Variant {
+        let named_variant = match self.variant_name.as_deref() {
+            Some("dark") => Some(Variant::Dark),
+            Some("light") => Some(Variant::Light),
+            _ => None,
+        };
+
+        match (named_variant.or(self.meta.variant), self.get(BACKGROUND)) {
+            (Some(variant), _) => variant,
+            (None, Some(background)) if background.luminance() >= 0.5 => Variant::Light,
+            (None, _) => Variant::Dark,
+        }
+    }
+
+    // Names of the colors defined by the user in the extra section,
+    // sorted alphabetically
+    pub fn extra_color_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .keys()
+            .map(|name| name.as_str())
+            .filter(|name| is_extra_color(name))
+            .collect();
+        names.sort();
+
+        names
+    }
+
+    // Mix of two colors, cached under `color1:amount:color2:space` so that
+    // each mix space gets its own entry
+    pub fn get_composite(
+        &mut self,
+        color1: &String,
+        amount: u8,
+        color2: &String,
+        space: MixSpace,
+    ) -> Option<&RGBA> {
+        let name = [color1.as_str(), &amount.to_string(), color2, space.name()]
+            .join(MIXED_COLOR_FIELD_SEPARATOR);
+
+        if self.colors.contains_key(&name) {
             return self.get(&name);
         }
 
-        let composite = self._make_composite(color1, amount, color2);
+        let composite = self._make_composite(color1, amount, color2, space);
 
         if composite.is_none() {
             return None;
         }
 
-        self.0.insert(name.clone(), composite.unwrap());
+        self.colors.insert(name.clone(), composite.unwrap());
 
         self.get(&name)
     }
 
-    fn _make_composite(&self, color1: &String, amount: u8, color2: &String) -> Option<RGB> {
+    fn _make_composite(
+        &self,
+        color1: &String,
+        amount: u8,
+        color2: &String,
+        space: MixSpace,
+    ) -> Option<RGBA> {
         let color1 = self.get(color1);
         let color2 = self.get(color2);
 
@@ -60,22 +468,35 @@ impl ColorTable {
             return None;
         }
 
-        Some(color1.unwrap().mix(amount, color2.unwrap()))
+        Some(color1.unwrap().mix_in(space, amount, color2.unwrap()))
     }
 }
 
+// Whether a color belongs to the extra section: that's any color that
+// is neither a standard one nor a composite one
+fn is_extra_color(name: &str) -> bool {
+    !COLOR_NAMES.contains(&name)
+        && !OPTIONAL_COLOR_NAMES.contains(&name)
+        && !BASE_COLOR_NAMES.contains(&name)
+        && name != EXTRA_SECTION
+        && name != EXTENDS
+        && name != META_SECTION
+        && name != VARIANTS_SECTION
+        && !name.contains(MIXED_COLOR_FIELD_SEPARATOR)
+}
+
 // Deref and DerefMut allows to access directly all the methods of the
 // HashMap
 impl Deref for ColorTable {
-    type Target = HashMap<String, RGB>;
+    type Target = HashMap<String, RGBA>;
 
-    fn deref(&self) -> &HashMap<String, RGB> {
-        &self.0
+    fn deref(&self) -> &HashMap<String, RGBA> {
+        &self.colors
     }
 }
 
 impl DerefMut for ColorTable {
-    fn deref_mut(&mut self) -> &mut HashMap<String, RGB> {
-        &mut self.0
+    fn deref_mut(&mut self) -> &mut HashMap<String, RGBA> {
+        &mut self.colors
     }
 }
@@ -1,12 +1,20 @@
-use crate::config::blueprint::directive::{HEX_6_DIGITS_WO_HASHTAG, HEX_6_DIGITS_W_HASHTAG};
+use super::color_format::ColorFormat;
+use crate::config::blueprint::directive::{
+    MIX_SPACE_HSL, MIX_SPACE_HSV, MIX_SPACE_LAB, MIX_SPACE_LINEAR_SRGB, MIX_SPACE_OKLAB,
+    MIX_SPACE_SRGB,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
 
 // Regex that recognizes HEX colors (#123456)
 static REGEX_HEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^#?[0-9a-fA-F]{6}$").unwrap());
 
 // RGB tuple
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RGB(pub u8, pub u8, pub u8);
 
 impl RGB {
@@ -29,28 +37,195 @@ impl RGB {
     }
 
     // Merges two RGB by weighting the current one by amount/100 and
-    // the second one by 1-amount/100
-    pub fn mix(&self, amount: u8, color: &Self) -> Self {
-        let percentage: f32 = amount as f32 / 100.0;
+    // the second one by 1-amount/100, in the given color space
+    pub fn mix_in(&self, space: MixSpace, amount: u8, color: &Self) -> Self {
+        let percentage: f32 = amount.min(100) as f32 / 100.0;
+        let _mix = |x: f32, y: f32| x * percentage + y * (1.0 - percentage);
 
-        let _mix = |x, y| (x as f32 * percentage + y as f32 * (1.0 - percentage)) as u8;
+        // Grays have no hue: the one of the other color is used.
+        // Otherwise the hue goes along the shortest arc
+        let _mix_hue = |h1: f32, s1: f32, h2: f32, s2: f32| match (s1 == 0.0, s2 == 0.0) {
+            (true, false) => h2,
+            (false, true) => h1,
+            _ => _mix(h1, h1 + ((h2 - h1 + 180.0).rem_euclid(360.0) - 180.0)),
+        };
 
-        Self(
-            _mix(self.0, color.0),
-            _mix(self.1, color.1),
-            _mix(self.2, color.2),
-        )
+        match space {
+            MixSpace::Srgb => {
+                let _mix_u8 = |x: u8, y: u8| _mix(x as f32, y as f32).round() as u8;
+                Self(
+                    _mix_u8(self.0, color.0),
+                    _mix_u8(self.1, color.1),
+                    _mix_u8(self.2, color.2),
+                )
+            }
+            MixSpace::LinearSrgb => {
+                let _mix_linear = |x: u8, y: u8| from_linear(_mix(to_linear(x), to_linear(y)));
+                Self(
+                    _mix_linear(self.0, color.0),
+                    _mix_linear(self.1, color.1),
+                    _mix_linear(self.2, color.2),
+                )
+            }
+            MixSpace::Oklab => {
+                let (l1, a1, b1) = self.to_oklab();
+                let (l2, a2, b2) = color.to_oklab();
+                Self::from_oklab(_mix(l1, l2), _mix(a1, a2), _mix(b1, b2))
+            }
+            MixSpace::Lab => {
+                let (l1, a1, b1) = self.to_lab();
+                let (l2, a2, b2) = color.to_lab();
+                Self::from_lab(_mix(l1, l2), _mix(a1, a2), _mix(b1, b2))
+            }
+            MixSpace::Hsl => {
+                let (h1, s1, l1) = self.to_hsl();
+                let (h2, s2, l2) = color.to_hsl();
+                Self::from_hsl(_mix_hue(h1, s1, h2, s2), _mix(s1, s2), _mix(l1, l2))
+            }
+            MixSpace::Hsv => {
+                let (h1, s1, v1) = self.to_hsv();
+                let (h2, s2, v2) = color.to_hsv();
+                Self::from_hsv(_mix_hue(h1, s1, h2, s2), _mix(s1, s2), _mix(v1, v2))
+            }
+        }
     }
 
     // Converts the RGB tuple into a String with a specific format
-    pub fn format(&self, format: &String) -> Result<String, String> {
-        let hex_str = format!("{:02X}{:02X}{:02X}", self.0, self.1, self.2);
+    // (see color_format). Colors are opaque
+    pub fn format(&self, format: &str) -> Result<String, String> {
+        Ok(ColorFormat::parse(format)?.render(self, u8::MAX))
+    }
+
+    // Converts the color to HSL: hue in degrees [0, 360), saturation and
+    // lightness in [0, 1]
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let r = self.0 as f32 / 255.0;
+        let g = self.1 as f32 / 255.0;
+        let b = self.2 as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
 
-        match format.as_str() {
-            HEX_6_DIGITS_W_HASHTAG => Ok(format!("#{}", hex_str)),
-            HEX_6_DIGITS_WO_HASHTAG => Ok(hex_str),
-            _ => Err(format!("Invalid color format `{}`", format)),
+        let lightness = (max + min) / 2.0;
+
+        // Shades of gray have neither hue nor saturation
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
         }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+
+        let hue = if max == r {
+            ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+
+        (hue * 60.0, saturation, lightness)
+    }
+
+    // Converts an HSL color back to RGB. The hue wraps around, while
+    // saturation and lightness are clamped to [0, 1]
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let m = lightness - chroma / 2.0;
+
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let _to_u8 = |c: f32| ((c + m) * 255.0).round() as u8;
+
+        Self(_to_u8(r), _to_u8(g), _to_u8(b))
+    }
+
+    // Converts an OKLCH color (lightness in [0, 1], chroma, hue in
+    // degrees) to RGB. Colors outside of the sRGB gamut are clipped
+    pub fn from_oklch(lightness: f32, chroma: f32, hue: f32) -> Self {
+        let (sin, cos) = hue.to_radians().sin_cos();
+        Self::from_oklab(lightness, chroma * cos, chroma * sin)
+    }
+
+    // Converts an OKLab color to RGB. Colors outside of the sRGB gamut are
+    // clipped
+    pub fn from_oklab(lightness: f32, a: f32, b: f32) -> Self {
+        // OKLab -> linear sRGB (https://bottosson.github.io/posts/oklab/)
+        let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m = (lightness - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+        Self::from_linear_rgb(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        )
+    }
+
+    // Converts the color to OKLab: lightness in [0, 1], a and b roughly in
+    // [-0.4, 0.4]
+    pub fn to_oklab(&self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_linear_rgb();
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    // Converts the color to OKLCH: lightness in [0, 1], chroma, hue in
+    // degrees [0, 360)
+    pub fn to_oklch(&self) -> (f32, f32, f32) {
+        let (l, a, b) = self.to_oklab();
+        (l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+    }
+
+    // Converts the color to linear light sRGB, each channel in [0, 1]
+    pub fn to_linear_rgb(&self) -> (f32, f32, f32) {
+        (to_linear(self.0), to_linear(self.1), to_linear(self.2))
+    }
+
+    // Converts a linear light sRGB color back to RGB. Channels outside of
+    // [0, 1] are clipped
+    pub fn from_linear_rgb(r: f32, g: f32, b: f32) -> Self {
+        Self(from_linear(r), from_linear(g), from_linear(b))
+    }
+
+    // Increases (or decreases, when negative) the HSL lightness by
+    // amount/100
+    pub fn lighten(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount / 100.0)
+    }
+
+    // Increases (or decreases, when negative) the HSL saturation by
+    // amount/100
+    pub fn saturate(&self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s + amount / 100.0, l)
+    }
+
+    // Rotates the hue by the given degrees
+    pub fn hue_rotate(&self, degrees: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h + degrees, s, l)
     }
 
     // Finds the luminance of a color
@@ -59,6 +234,278 @@ impl RGB {
         (0.2126_f32 * self.0 as f32 + 0.7152_f32 * self.1 as f32 + 0.0722_f32 * self.2 as f32)
             / 255_f32
     }
+
+    // Relative luminance, as defined by WCAG 2 (linear sRGB)
+    pub fn relative_luminance(&self) -> f32 {
+        let (r, g, b) = self.to_linear_rgb();
+
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    // WCAG 2 contrast ratio between two colors, from 1 to 21
+    pub fn contrast_ratio(&self, color: &Self) -> f32 {
+        let l1 = self.relative_luminance();
+        let l2 = color.relative_luminance();
+
+        (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+    }
+
+    // Converts the color to HSV: hue in degrees [0, 360), saturation and
+    // value in [0, 1]
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, _, _) = self.to_hsl();
+        let max = self.0.max(self.1).max(self.2) as f32 / 255.0;
+        let min = self.0.min(self.1).min(self.2) as f32 / 255.0;
+
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        (hue, saturation, max)
+    }
+
+    // Converts an HSV color back to RGB. The hue wraps around, while
+    // saturation and value are clamped to [0, 1]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        // HSV -> HSL
+        let lightness = value * (1.0 - saturation / 2.0);
+        let saturation = match lightness {
+            l if l == 0.0 || l == 1.0 => 0.0,
+            l => (value - l) / l.min(1.0 - l),
+        };
+
+        Self::from_hsl(hue, saturation, lightness)
+    }
+
+    // Converts the color to CIELAB (D65 white point): lightness in
+    // [0, 100], a and b roughly in [-128, 127]
+    pub fn to_lab(&self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_linear_rgb();
+
+        // Linear sRGB -> XYZ, relative to the white point
+        let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / D65_WHITE.0;
+        let y = (0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b) / D65_WHITE.1;
+        let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / D65_WHITE.2;
+
+        let _f = |t: f32| {
+            if t > LAB_EPSILON.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * LAB_EPSILON.powi(2)) + 4.0 / 29.0
+            }
+        };
+        let (fx, fy, fz) = (_f(x), _f(y), _f(z));
+
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    // Converts a CIELAB color (D65 white point) to RGB. Colors outside of
+    // the sRGB gamut are clipped
+    pub fn from_lab(lightness: f32, a: f32, b: f32) -> Self {
+        let fy = (lightness + 16.0) / 116.0;
+        let (fx, fz) = (fy + a / 500.0, fy - b / 200.0);
+
+        let _f_inv = |t: f32| {
+            if t > LAB_EPSILON {
+                t.powi(3)
+            } else {
+                3.0 * LAB_EPSILON.powi(2) * (t - 4.0 / 29.0)
+            }
+        };
+        let x = _f_inv(fx) * D65_WHITE.0;
+        let y = _f_inv(fy) * D65_WHITE.1;
+        let z = _f_inv(fz) * D65_WHITE.2;
+
+        // XYZ -> linear sRGB
+        Self::from_linear_rgb(
+            3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+            -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+            0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+        )
+    }
+
+    // CIEDE2000 color difference: below 1 two colors look the same, 2.3
+    // is a just noticeable difference
+    pub fn delta_e(&self, color: &Self) -> f32 {
+        delta_e_2000(self.to_lab(), color.to_lab())
+    }
+}
+
+// Formats the color as `#RRGGBB`
+impl fmt::Display for RGB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+    }
+}
+
+// Parses `#RRGGBB`, `RRGGBB` and the `#RGB` shorthand
+impl FromStr for RGB {
+    type Err = String;
+
+    fn from_str(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        if digits.len() == 3 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            let expanded: String = digits.chars().flat_map(|c| [c, c]).collect();
+            return Self::new_from_hex(&expanded);
+        }
+
+        Self::new_from_hex(hex)
+    }
+}
+
+// Colors are (de)serialized as `#RRGGBB` strings
+impl Serialize for RGB {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RGB {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex.parse().map_err(de::Error::custom)
+    }
+}
+
+// D65 reference white, in XYZ
+const D65_WHITE: (f32, f32, f32) = (0.950_47, 1.0, 1.088_83);
+// Threshold between the linear and the cubic part of the CIELAB
+// transfer function
+const LAB_EPSILON: f32 = 6.0 / 29.0;
+
+// CIEDE2000 difference between two CIELAB colors
+// (https://hajim.rochester.edu/ece/sites/gsharma/ciede2000/)
+pub fn delta_e_2000(lab1: (f32, f32, f32), lab2: (f32, f32, f32)) -> f32 {
+    let (l1, a1, b1) = (lab1.0 as f64, lab1.1 as f64, lab1.2 as f64);
+    let (l2, a2, b2) = (lab2.0 as f64, lab2.1 as f64, lab2.2 as f64);
+    let pow25_7 = 25_f64.powi(7);
+
+    // Chroma correction of a*
+    let c_mean = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + pow25_7)).sqrt());
+    let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let _hue = |a: f64, b: f64| match a == 0.0 && b == 0.0 {
+        true => 0.0,
+        false => b.atan2(a).to_degrees().rem_euclid(360.0),
+    };
+    let (h1, h2) = (_hue(a1, b1), _hue(a2, b2));
+
+    // Differences
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = match c1 * c2 == 0.0 {
+        true => 0.0,
+        false => match h2 - h1 {
+            d if d > 180.0 => d - 360.0,
+            d if d < -180.0 => d + 360.0,
+            d => d,
+        },
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    // Means
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = match (c1 * c2 == 0.0, (h1 - h2).abs() > 180.0) {
+        (true, _) => h1 + h2,
+        (false, false) => (h1 + h2) / 2.0,
+        (false, true) if h1 + h2 < 360.0 => (h1 + h2 + 360.0) / 2.0,
+        (false, true) => (h1 + h2 - 360.0) / 2.0,
+    };
+
+    // Weighting functions
+    let cos = |degrees: f64| degrees.to_radians().cos();
+    let t =
+        1.0 - 0.17 * cos(h_mean - 30.0) + 0.24 * cos(2.0 * h_mean) + 0.32 * cos(3.0 * h_mean + 6.0)
+            - 0.20 * cos(4.0 * h_mean - 63.0);
+    let delta_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_mean.powi(7) / (c_mean.powi(7) + pow25_7)).sqrt();
+    let s_l = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+
+    (l * l + c * c + h * h + r_t * c * h).sqrt() as f32
+}
+
+// sRGB channel -> linear light, in [0, 1]
+fn to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Linear light -> sRGB channel. Values outside of [0, 1] are clipped
+fn from_linear(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+// Color spaces two colors can be mixed in. Gamma encoded sRGB is what
+// most tools do, but its mixes come out too dark: linear light sRGB
+// blends like light does, OKLab keeps the perceived lightness even
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MixSpace {
+    #[default]
+    Srgb,
+    LinearSrgb,
+    Oklab,
+    Lab,
+    Hsl,
+    Hsv,
+}
+
+impl MixSpace {
+    pub const NAMES: [&'static str; 6] = [
+        MIX_SPACE_SRGB,
+        MIX_SPACE_LINEAR_SRGB,
+        MIX_SPACE_OKLAB,
+        MIX_SPACE_LAB,
+        MIX_SPACE_HSL,
+        MIX_SPACE_HSV,
+    ];
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            MIX_SPACE_SRGB => Ok(MixSpace::Srgb),
+            MIX_SPACE_LINEAR_SRGB => Ok(MixSpace::LinearSrgb),
+            MIX_SPACE_OKLAB => Ok(MixSpace::Oklab),
+            MIX_SPACE_LAB => Ok(MixSpace::Lab),
+            MIX_SPACE_HSL => Ok(MixSpace::Hsl),
+            MIX_SPACE_HSV => Ok(MixSpace::Hsv),
+            _ => Err(format!(
+                "Invalid mix space `{}`. Valid mix spaces are `{}`",
+                name,
+                MixSpace::NAMES.join("`, `")
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MixSpace::Srgb => MIX_SPACE_SRGB,
+            MixSpace::LinearSrgb => MIX_SPACE_LINEAR_SRGB,
+            MixSpace::Oklab => MIX_SPACE_OKLAB,
+            MixSpace::Lab => MIX_SPACE_LAB,
+            MixSpace::Hsl => MIX_SPACE_HSL,
+            MixSpace::Hsv => MIX_SPACE_HSV,
+        }
+    }
 }
 
 // Test module
@@ -94,6 +541,223 @@ mod tests {
         assert!(rgb.is_err());
     }
 
+    #[test]
+    fn hsl_round_trip() {
+        let rgb = RGB::new_from_hex("#80ED99").unwrap();
+        let (h, s, l) = rgb.to_hsl();
+        assert!((h - 133.8).abs() < 0.1);
+        assert_eq!(RGB::from_hsl(h, s, l), rgb);
+    }
+
+    #[test]
+    fn hsl_adjustments() {
+        let red = RGB(255, 0, 0);
+        assert_eq!(red.lighten(50.0), RGB(255, 255, 255));
+        assert_eq!(red.lighten(-50.0), RGB(0, 0, 0));
+        assert_eq!(red.saturate(-100.0), RGB(128, 128, 128));
+        assert_eq!(red.hue_rotate(120.0), RGB(0, 255, 0));
+        assert_eq!(red.hue_rotate(-120.0), RGB(0, 0, 255));
+    }
+
+    #[test]
+    fn oklab_round_trip() {
+        // Reference values from https://bottosson.github.io/posts/oklab/
+        let (l, a, b) = RGB(255, 255, 255).to_oklab();
+        assert!((l - 1.0).abs() < 0.001 && a.abs() < 0.001 && b.abs() < 0.001);
+        let (l, c, h) = RGB(255, 0, 0).to_oklch();
+        assert!((l - 0.628).abs() < 0.001);
+        assert!((c - 0.2577).abs() < 0.001);
+        assert!((h - 29.23).abs() < 0.1);
+
+        for rgb in [RGB(0x80, 0xED, 0x99), RGB(12, 34, 56), RGB(0, 0, 255)] {
+            let (l, a, b) = rgb.to_oklab();
+            assert_eq!(RGB::from_oklab(l, a, b), rgb);
+            let (l, c, h) = rgb.to_oklch();
+            assert_eq!(RGB::from_oklch(l, c, h), rgb);
+        }
+    }
+
+    #[test]
+    fn mix_spaces() {
+        let red = RGB(255, 0, 0);
+        let lime = RGB(0, 255, 0);
+        let black = RGB(0, 0, 0);
+        let white = RGB(255, 255, 255);
+
+        // Halves are rounded, not truncated
+        assert_eq!(red.mix_in(MixSpace::Srgb, 50, &black), RGB(128, 0, 0));
+        assert_eq!(
+            RGB(1, 2, 3).mix_in(MixSpace::Srgb, 50, &RGB(2, 3, 4)),
+            RGB(2, 3, 4)
+        );
+
+        // Linear light: 50% white is lighter than the gamma encoded 50%
+        assert_eq!(
+            white.mix_in(MixSpace::LinearSrgb, 50, &black),
+            RGB(188, 188, 188)
+        );
+        assert_eq!(
+            red.mix_in(MixSpace::LinearSrgb, 50, &lime),
+            RGB(188, 188, 0)
+        );
+
+        // OKLab: lightness is interpolated perceptually
+        let (l, _, _) = white.mix_in(MixSpace::Oklab, 50, &black).to_oklab();
+        assert!((l - 0.5).abs() < 0.005);
+
+        // HSL: along the shortest hue arc, grays take the other hue
+        assert_eq!(red.mix_in(MixSpace::Hsl, 50, &lime), RGB(255, 255, 0));
+        assert_eq!(
+            RGB(255, 0, 64)
+                .mix_in(MixSpace::Hsl, 50, &RGB(255, 64, 0))
+                .to_hsl()
+                .0,
+            0.0
+        );
+        assert_eq!(red.mix_in(MixSpace::Hsl, 50, &white).to_hsl().0, 0.0);
+
+        // CIELAB and HSV: halfway in lightness, and along the hue arc
+        let (l, _, _) = white.mix_in(MixSpace::Lab, 50, &black).to_lab();
+        assert!((l - 50.0).abs() < 0.5);
+        assert_eq!(red.mix_in(MixSpace::Hsv, 50, &lime), RGB(255, 255, 0));
+        // Black keeps the hue of red, but has no saturation either
+        assert_eq!(red.mix_in(MixSpace::Hsv, 50, &black), RGB(128, 64, 64));
+
+        // The bounds are the colors themselves in every space
+        for space in [
+            MixSpace::Srgb,
+            MixSpace::LinearSrgb,
+            MixSpace::Oklab,
+            MixSpace::Lab,
+            MixSpace::Hsl,
+            MixSpace::Hsv,
+        ] {
+            let color = RGB(0x80, 0xED, 0x99);
+            assert_eq!(color.mix_in(space, 100, &black), color);
+            assert_eq!(black.mix_in(space, 0, &color), color);
+            assert_eq!(MixSpace::from_name(space.name()), Ok(space));
+        }
+        assert!(MixSpace::from_name("cmyk").is_err());
+    }
+
+    #[test]
+    fn hsv() {
+        // Reference values from https://www.rapidtables.com/convert/color/rgb-to-hsv.html
+        let (h, s, v) = RGB(0x80, 0xED, 0x99).to_hsv();
+        assert!((h - 133.8).abs() < 0.1);
+        assert!((s - 0.46).abs() < 0.005);
+        assert!((v - 0.929).abs() < 0.001);
+
+        assert_eq!(RGB(0, 0, 0).to_hsv(), (0.0, 0.0, 0.0));
+        assert_eq!(RGB::from_hsv(0.0, 1.0, 1.0), RGB(255, 0, 0));
+        assert_eq!(RGB::from_hsv(240.0, 1.0, 0.5), RGB(0, 0, 128));
+        assert_eq!(RGB::from_hsv(0.0, 0.0, 0.75), RGB(191, 191, 191));
+
+        for rgb in [RGB(0x80, 0xED, 0x99), RGB(12, 34, 56), RGB(255, 255, 255)] {
+            let (h, s, v) = rgb.to_hsv();
+            assert_eq!(RGB::from_hsv(h, s, v), rgb);
+        }
+    }
+
+    #[test]
+    fn lab() {
+        // Reference values from http://www.brucelindbloom.com (D65, sRGB)
+        let close = |(l1, a1, b1): (f32, f32, f32), (l2, a2, b2): (f32, f32, f32)| {
+            (l1 - l2).abs() < 0.01 && (a1 - a2).abs() < 0.01 && (b1 - b2).abs() < 0.01
+        };
+        assert!(close(RGB(255, 255, 255).to_lab(), (100.0, 0.0, 0.0)));
+        assert!(close(RGB(255, 0, 0).to_lab(), (53.24, 80.09, 67.20)));
+        assert!(close(RGB(0, 0, 255).to_lab(), (32.30, 79.19, -107.86)));
+        assert!(close(RGB(0, 0, 0).to_lab(), (0.0, 0.0, 0.0)));
+
+        for rgb in [RGB(0x80, 0xED, 0x99), RGB(12, 34, 56), RGB(1, 1, 1)] {
+            let (l, a, b) = rgb.to_lab();
+            assert_eq!(RGB::from_lab(l, a, b), rgb);
+        }
+    }
+
+    #[test]
+    fn linear_rgb() {
+        assert_eq!(RGB(255, 255, 255).to_linear_rgb(), (1.0, 1.0, 1.0));
+        let (r, _, _) = RGB(128, 0, 0).to_linear_rgb();
+        assert!((r - 0.2158605).abs() < 1e-6);
+        assert_eq!(RGB::from_linear_rgb(0.2158605, 2.0, -1.0), RGB(128, 255, 0));
+    }
+
+    #[test]
+    fn delta_e() {
+        // Test data of Sharma, Wu and Dalal, "The CIEDE2000 color-difference
+        // formula: implementation notes, supplementary test data, and
+        // mathematical observations"
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 3.1571, -77.2803), (50.0, 0.0, -82.7485), 2.8615),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.49, -0.001), (50.0, -2.49, 0.0011), 7.2195),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                (22.7233, 20.0904, -46.694),
+                (23.0331, 14.973, -42.5619),
+                2.0373,
+            ),
+            (
+                (90.9257, -0.5406, -0.9208),
+                (88.6381, -0.8985, -0.7239),
+                1.5381,
+            ),
+            ((2.0776, 0.0795, -1.135), (0.9033, -0.0636, -0.5514), 0.9082),
+        ];
+        for (lab1, lab2, expected) in pairs {
+            let delta = delta_e_2000(lab1, lab2);
+            assert!(
+                (delta - expected).abs() < 0.001,
+                "{} instead of {}",
+                delta,
+                expected
+            );
+            assert!((delta_e_2000(lab2, lab1) - expected).abs() < 0.001);
+        }
+
+        let color = RGB(0x80, 0xED, 0x99);
+        assert_eq!(color.delta_e(&color), 0.0);
+        assert!(color.delta_e(&RGB(0x80, 0xED, 0x9A)) < 1.0);
+    }
+
+    #[test]
+    fn string_conversions() {
+        let rgb: RGB = "#80ED99".parse().unwrap();
+        assert_eq!(rgb, RGB(128, 237, 153));
+        assert_eq!(rgb.to_string(), "#80ED99");
+        assert_eq!(" 80ed99 ".parse::<RGB>(), Ok(rgb.clone()));
+        assert_eq!("#F80".parse::<RGB>(), Ok(RGB(0xFF, 0x88, 0)));
+        assert!("#F8".parse::<RGB>().is_err());
+        assert!("#GG0".parse::<RGB>().is_err());
+
+        // Serde goes through the same string representation
+        let json = serde_json::to_string(&rgb).unwrap();
+        assert_eq!(json, r##""#80ED99""##);
+        assert_eq!(serde_json::from_str::<RGB>(&json).unwrap(), rgb);
+        assert!(serde_json::from_str::<RGB>(r#""red""#).is_err());
+        assert!(serde_json::from_str::<RGB>("12").is_err());
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let black = RGB(0, 0, 0);
+        let white = RGB(255, 255, 255);
+        assert!((black.contrast_ratio(&white) - 21.0).abs() < 0.01);
+        assert!((white.contrast_ratio(&white) - 1.0).abs() < 0.01);
+
+        // #777777 on white is the classic 4.48:1
+        let gray = RGB(0x77, 0x77, 0x77);
+        assert!((gray.contrast_ratio(&white) - 4.48).abs() < 0.01);
+    }
+
     #[test]
     fn wrong_length() {
         let rgb = RGB::new_from_hex("#000");
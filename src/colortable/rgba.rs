@@ -0,0 +1,158 @@
+use super::color_format::ColorFormat;
+use super::rgb::{MixSpace, RGB};
+use crate::config::colorscheme::HEX_COLOR_PATTERN;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ops::Deref;
+
+// Regex that recognizes HEX colors, with or without alpha channel
+// (#123456 or #12345678)
+static REGEX_HEX_COLOR: Lazy<Regex> = Lazy::new(|| Regex::new(HEX_COLOR_PATTERN).unwrap());
+
+// RGB color plus an alpha channel, from 0 (transparent) to 255
+// (opaque). Deref gives access to all the methods of the RGB color
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RGBA {
+    pub rgb: RGB,
+    pub alpha: u8,
+}
+
+impl RGBA {
+    // Converts an HEX color into an RGBA color. Both 6 digits (opaque)
+    // and 8 digits (with alpha channel) colors are accepted
+    pub fn new_from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+
+        if !REGEX_HEX_COLOR.is_match(hex) {
+            return Err(format!("Invalid hex color `{}`", hex));
+        }
+
+        // Removes the starting hashtag
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() == 6 {
+            return RGB::new_from_hex(hex).map(Self::from);
+        }
+
+        let rgb = RGB::new_from_hex(&hex[0..6])?;
+        let alpha = u8::from_str_radix(&hex[6..8], 16).unwrap();
+
+        Ok(Self { rgb, alpha })
+    }
+
+    // Copy of the color with a different opacity, in percentage
+    pub fn with_opacity(&self, opacity: u8) -> Self {
+        let opacity = opacity.min(100) as f32 / 100.0;
+
+        Self {
+            rgb: self.rgb.clone(),
+            alpha: (opacity * 255.0).round() as u8,
+        }
+    }
+
+    // Applies an operation to the RGB color, keeping the alpha channel
+    pub fn map_rgb(&self, operation: impl FnOnce(&RGB) -> RGB) -> Self {
+        Self {
+            rgb: operation(&self.rgb),
+            alpha: self.alpha,
+        }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.alpha == u8::MAX
+    }
+
+    // Merges two colors, alpha channel included, by weighting the
+    // current one by amount/100 and the second one by 1-amount/100
+    pub fn mix(&self, amount: u8, color: &Self) -> Self {
+        self.mix_in(MixSpace::Srgb, amount, color)
+    }
+
+    // Same as mix, with the colors interpolated in a specific color
+    // space. The alpha channel is always interpolated linearly
+    pub fn mix_in(&self, space: MixSpace, amount: u8, color: &Self) -> Self {
+        let percentage: f32 = amount.min(100) as f32 / 100.0;
+
+        let alpha = self.alpha as f32 * percentage + color.alpha as f32 * (1.0 - percentage);
+
+        Self {
+            rgb: self.rgb.mix_in(space, amount, &color.rgb),
+            alpha: alpha.round() as u8,
+        }
+    }
+
+    // Converts the color into a String with a specific format. The 6
+    // digits formats drop the alpha channel
+    pub fn format(&self, format: &str) -> Result<String, String> {
+        Ok(ColorFormat::parse(format)?.render(&self.rgb, self.alpha))
+    }
+}
+
+impl From<RGB> for RGBA {
+    fn from(rgb: RGB) -> Self {
+        Self {
+            rgb,
+            alpha: u8::MAX,
+        }
+    }
+}
+
+impl Deref for RGBA {
+    type Target = RGB;
+
+    fn deref(&self) -> &RGB {
+        &self.rgb
+    }
+}
+
+// An RGB color is an opaque RGBA color
+impl PartialEq<RGB> for RGBA {
+    fn eq(&self, other: &RGB) -> bool {
+        self.is_opaque() && self.rgb == *other
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::blueprint::directive::{
+        HEX_6_DIGITS_W_HASHTAG, HEX_8_DIGITS_WO_HASHTAG, HEX_8_DIGITS_W_HASHTAG, RGBA_FUNCTION,
+    };
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(RGBA::new_from_hex("#80ED99"), Ok(RGB(128, 237, 153).into()));
+        assert_eq!(
+            RGBA::new_from_hex(" 80ED9940 "),
+            Ok(RGBA {
+                rgb: RGB(128, 237, 153),
+                alpha: 64
+            })
+        );
+        assert!(RGBA::new_from_hex("#80ED994").is_err());
+        assert!(RGBA::new_from_hex("#80ED99y0").is_err());
+    }
+
+    #[test]
+    fn mix_alpha() {
+        let transparent = RGBA::from(RGB(0, 0, 0)).with_opacity(0);
+        let opaque = RGBA::from(RGB(200, 100, 0));
+
+        let mixed = opaque.mix(50, &transparent);
+        assert_eq!(mixed.rgb, RGB(100, 50, 0));
+        assert_eq!(mixed.alpha, 128);
+    }
+
+    #[test]
+    fn format_alpha() {
+        let color = RGBA::from(RGB(16, 32, 48)).with_opacity(80);
+        assert_eq!(color.alpha, 204);
+
+        let format = |f: &str| color.format(f).unwrap();
+        assert_eq!(format(HEX_8_DIGITS_W_HASHTAG), "#102030CC");
+        assert_eq!(format(HEX_8_DIGITS_WO_HASHTAG), "102030CC");
+        assert_eq!(format(RGBA_FUNCTION), "rgba(16, 32, 48, 0.8)");
+        assert_eq!(format(HEX_6_DIGITS_W_HASHTAG), "#102030");
+    }
+}
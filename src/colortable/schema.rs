@@ -0,0 +1,216 @@
+// JSON Schema of the colorscheme format, for editors that support
+// validation and completion (e.g. `"$schema"` in VS Code settings, or
+// yaml-language-server).
+//
+// The schema is generated from the same constants the ColorTableVisitor
+// enforces, so it can't drift from what the parser accepts. Expressions
+// are only checked loosely: the parser reports the details.
+
+use super::meta::Meta;
+use crate::config::colorscheme::{
+    BASE_COLOR_NAMES, COLOR_EXPRESSION_PATTERN, COLOR_NAMES, EXTENDS, EXTRA_COLOR_NAME_PATTERN,
+    EXTRA_SECTION, HEX_COLOR_PATTERN, META_SECTION, OPTIONAL_COLOR_DEFAULTS, OPTIONAL_COLOR_NAMES,
+    SCHEMA_KEY, VARIANTS_SECTION,
+};
+use crate::config::info::APP_NAME;
+use serde_json::{json, Map, Value};
+
+const DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+pub fn json_schema() -> Value {
+    let mut properties = color_properties();
+
+    properties.insert(
+        EXTENDS.to_string(),
+        json!({
+            "description": "Name of the parent colorscheme, which provides all the colors that aren't defined here",
+            "type": "string",
+        }),
+    );
+    properties.insert(META_SECTION.to_string(), Meta::json_schema());
+    properties.insert(
+        SCHEMA_KEY.to_string(),
+        json!({
+            "description": "URL or path of this schema",
+            "type": "string",
+        }),
+    );
+
+    // Variants hold colors and their own metadata
+    let mut variant_properties = color_properties();
+    variant_properties.insert(META_SECTION.to_string(), Meta::json_schema());
+    properties.insert(
+        VARIANTS_SECTION.to_string(),
+        json!({
+            "description": "Named variants (e.g. `dark` and `light`), merged over the colors shared by all the variants. The first one is used by default",
+            "type": "object",
+            "minProperties": 1,
+            "propertyNames": { "pattern": EXTRA_COLOR_NAME_PATTERN },
+            "additionalProperties": {
+                "type": "object",
+                "properties": variant_properties,
+                "additionalProperties": false,
+            },
+        }),
+    );
+
+    json!({
+        "$schema": DRAFT,
+        "title": format!("{} colorscheme", APP_NAME),
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+        // Required colors can be left to the parent colorscheme or to
+        // the variants
+        "if": {
+            "not": {
+                "anyOf": [
+                    { "required": [EXTENDS] },
+                    { "required": [VARIANTS_SECTION] },
+                ],
+            },
+        },
+        "then": { "required": COLOR_NAMES },
+        "definitions": {
+            "color": {
+                "type": "string",
+                "anyOf": [
+                    {
+                        "description": "Hex color, with optional alpha channel",
+                        "pattern": HEX_COLOR_PATTERN,
+                    },
+                    {
+                        "description": "Expression: another color, a CSS color or a function such as `lighten(color_01, 10%)`",
+                        "pattern": COLOR_EXPRESSION_PATTERN,
+                    },
+                ],
+            },
+        },
+    })
+}
+
+// Colors and extra section, allowed both at the top level and inside the
+// variants
+fn color_properties() -> Map<String, Value> {
+    let mut properties = Map::new();
+
+    for name in COLOR_NAMES {
+        properties.insert(name.to_string(), color_ref(None));
+    }
+    for (name, color1, amount, color2) in OPTIONAL_COLOR_DEFAULTS {
+        let default = match amount {
+            100 => format!("Defaults to `{}`", color1),
+            _ => format!(
+                "Defaults to `{}` mixed {}% over `{}`",
+                color1, amount, color2
+            ),
+        };
+        properties.insert(name.to_string(), color_ref(Some(default)));
+    }
+    for name in BASE_COLOR_NAMES {
+        properties.insert(
+            name.to_string(),
+            color_ref(Some(
+                "Color of the original base16/base24 palette".to_string(),
+            )),
+        );
+    }
+
+    // Extra colors can't clash with any other key
+    let reserved: Vec<&str> = COLOR_NAMES
+        .iter()
+        .chain(OPTIONAL_COLOR_NAMES.iter())
+        .chain(BASE_COLOR_NAMES.iter())
+        .chain([EXTRA_SECTION, EXTENDS, META_SECTION, VARIANTS_SECTION].iter())
+        .copied()
+        .collect();
+    properties.insert(
+        EXTRA_SECTION.to_string(),
+        json!({
+            "description": "Colors defined by the user, usable as `{name}` inside blueprints",
+            "type": "object",
+            "propertyNames": {
+                "pattern": EXTRA_COLOR_NAME_PATTERN,
+                "not": { "enum": reserved },
+            },
+            "additionalProperties": { "$ref": "#/definitions/color" },
+        }),
+    );
+
+    properties
+}
+
+fn color_ref(description: Option<String>) -> Value {
+    match description {
+        Some(description) => json!({
+            "description": description,
+            "$ref": "#/definitions/color",
+        }),
+        None => json!({ "$ref": "#/definitions/color" }),
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::expression::Expression;
+    use crate::colortable::rgba::RGBA;
+    use regex::Regex;
+
+    #[test]
+    fn keys() {
+        let schema = json_schema();
+
+        let required: Vec<&str> = schema["then"]["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|name| name.as_str().unwrap())
+            .collect();
+        assert_eq!(required, COLOR_NAMES);
+
+        let properties = schema["properties"].as_object().unwrap();
+        assert_eq!(
+            properties.len(),
+            COLOR_NAMES.len() + OPTIONAL_COLOR_NAMES.len() + BASE_COLOR_NAMES.len() + 5
+        );
+        let variant = &properties["variants"]["additionalProperties"]["properties"];
+        assert_eq!(variant.as_object().unwrap().len(), properties.len() - 3);
+        assert_eq!(
+            properties["selection_background"]["description"],
+            "Defaults to `foreground` mixed 30% over `background`"
+        );
+    }
+
+    #[test]
+    fn color_patterns() {
+        let hex = Regex::new(HEX_COLOR_PATTERN).unwrap();
+        let expression = Regex::new(COLOR_EXPRESSION_PATTERN).unwrap();
+        let accepted = |value: &str| hex.is_match(value) || expression.is_match(value);
+
+        // Everything the parser accepts is accepted by the schema
+        for value in [
+            "#80ED99",
+            " 80ED99 ",
+            "#80ED9940",
+            "#F80",
+            "foreground",
+            "rebeccapurple",
+            "lighten(color_01, 10%)",
+            "hue-rotate(color_10, 120deg)",
+            "rgb(255 0 0 / 50%)",
+        ] {
+            assert!(
+                RGBA::new_from_hex(value).is_ok() || Expression::parse(value).is_ok(),
+                "{}",
+                value
+            );
+            assert!(accepted(value), "{}", value);
+        }
+
+        for value in ["", "#80ED9", "12 34", "(color_01)"] {
+            assert!(!accepted(value), "{}", value);
+        }
+    }
+}
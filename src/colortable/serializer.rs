@@ -0,0 +1,100 @@
+use super::{is_extra_color, ColorTable};
+use crate::config::blueprint::directive::{HEX_6_DIGITS_W_HASHTAG, HEX_8_DIGITS_W_HASHTAG};
+use crate::config::blueprint::MIXED_COLOR_FIELD_SEPARATOR;
+use crate::config::colorscheme::{COLOR_NAMES, EXTRA_SECTION, META_SECTION};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::collections::BTreeMap;
+
+// This is the trait that informs Serde how to serialize ColorTable.
+// Required colors come first, then the optional ones and the sections.
+// Derived and composite colors are a cache and are never serialized.
+impl Serialize for ColorTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut optional_names: Vec<&str> = self
+            .keys()
+            .map(|name| name.as_str())
+            .filter(|name| !COLOR_NAMES.contains(name))
+            .filter(|name| !is_extra_color(name))
+            .filter(|name| !self.is_derived(name))
+            .filter(|name| !name.contains(MIXED_COLOR_FIELD_SEPARATOR))
+            .collect();
+        optional_names.sort();
+
+        let names: Vec<&str> = COLOR_NAMES
+            .iter()
+            .copied()
+            .filter(|name| self.contains_key(*name))
+            .chain(optional_names)
+            .collect();
+
+        // Translucent colors keep their alpha channel
+        let hex = |name: &str| {
+            let color = &self[name];
+            let format = match color.is_opaque() {
+                true => HEX_6_DIGITS_W_HASHTAG,
+                false => HEX_8_DIGITS_W_HASHTAG,
+            };
+            color.format(format).unwrap()
+        };
+        let extra_colors: BTreeMap<&str, String> = self
+            .extra_color_names()
+            .into_iter()
+            .map(|name| (name, hex(name)))
+            .collect();
+
+        let mut map = serializer.serialize_map(None)?;
+        for name in names {
+            map.serialize_entry(name, &hex(name))?;
+        }
+
+        // The sections come last, as TOML requires tables to follow
+        // plain values
+        if !self.meta().is_empty() {
+            map.serialize_entry(META_SECTION, self.meta())?;
+        }
+        if !extra_colors.is_empty() {
+            map.serialize_entry(EXTRA_SECTION, &extra_colors)?;
+        }
+
+        map.end()
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colortable::rgb::RGB;
+    use crate::colortable::rgba::RGBA;
+
+    #[test]
+    fn required_colors_first() {
+        let mut colors = ColorTable::with_capacity(4);
+        colors.insert("base00".to_string(), RGB(0, 0, 0).into());
+        colors.insert("accent".to_string(), RGB(255, 136, 0).into());
+        colors.insert("foreground".to_string(), RGB(255, 255, 255).into());
+        colors.insert("background".to_string(), RGB(16, 32, 48).into());
+        colors.insert("background:50:foreground".to_string(), RGB(0, 0, 0).into());
+
+        let json = serde_json::to_string(&colors).unwrap();
+        assert_eq!(
+            json,
+            r##"{"background":"#102030","foreground":"#FFFFFF","base00":"#000000","extra":{"accent":"#FF8800"}}"##
+        );
+    }
+
+    #[test]
+    fn translucent_colors() {
+        let mut colors = ColorTable::with_capacity(1);
+        colors.insert(
+            "background".to_string(),
+            RGBA::from(RGB(16, 32, 48)).with_opacity(80),
+        );
+
+        let json = serde_json::to_string(&colors).unwrap();
+        assert_eq!(json, r##"{"background":"#102030CC"}"##);
+    }
+}
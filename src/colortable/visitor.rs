@@ -1,14 +1,27 @@
 // Deserialization of a custom map.
 // See https://serde.rs/deserialize-map.html for the detailed explanation
 
-use super::rgb::RGB;
+use super::expression::Expression;
+use super::is_extra_color;
+use super::meta::Meta;
+use super::rgba::RGBA;
 use super::ColorTable;
-use crate::config::colorscheme::COLOR_NAMES;
+use crate::config::colorscheme::{
+    BASE_COLOR_NAMES, COLOR_NAMES, EXTENDS, EXTRA_COLOR_NAME_PATTERN, EXTRA_SECTION, META_SECTION,
+    OPTIONAL_COLOR_NAMES, SCHEMA_KEY, VARIANTS_SECTION,
+};
 use const_format::formatcp;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
 use std::fmt;
 use std::marker::PhantomData;
 
+// Regex that recognizes valid names for extra colors. Those must be
+// usable as `{name}` inside blueprints
+static REGEX_EXTRA_COLOR_NAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(EXTRA_COLOR_NAME_PATTERN).unwrap());
+
 // A Visitor is a type that holds methods that a Deserializer can drive
 // depending on what is contained in the input data.
 //
@@ -19,12 +32,24 @@ use std::marker::PhantomData;
 // parameters.
 struct ColorTableVisitor {
     marker: PhantomData<fn() -> ColorTable>,
+    // Whether the table is a variant inside the variants section. Variants
+    // only hold colors, and are completed once merged with the shared
+    // colors
+    is_variant: bool,
 }
 
 impl ColorTableVisitor {
     fn new() -> Self {
         ColorTableVisitor {
             marker: PhantomData,
+            is_variant: false,
+        }
+    }
+
+    fn new_variant() -> Self {
+        ColorTableVisitor {
+            marker: PhantomData,
+            is_variant: true,
         }
     }
 }
@@ -53,44 +78,255 @@ impl<'de> Visitor<'de> for ColorTableVisitor {
     {
         let mut colors = ColorTable::with_capacity(access.size_hint().unwrap_or(0));
 
+        let mut has_extra_section = false;
+        let mut has_meta_section = false;
+        let mut has_variants_section = false;
+
         // Adds each entry into the ColorTable
-        while let Some((color_name, color_hex)) = access.next_entry()? {
-            // Checks whether color_name was already present
-            if colors.contains_key(color_name) {
+        while let Some(key) = access.next_key::<String>()? {
+            // Variants can't be nested, nor have their own parent
+            if self.is_variant && [EXTENDS, VARIANTS_SECTION].contains(&key.as_str()) {
                 return Err(de::Error::custom(format!(
-                    "Color `{}` was already defined",
-                    color_name
+                    "Key `{}` isn't allowed inside a variant",
+                    key
                 )));
             }
 
+            // Named variants of the colorscheme
+            if key == VARIANTS_SECTION {
+                if has_variants_section {
+                    return Err(de::Error::custom(format!(
+                        "Section `{}` was already defined",
+                        VARIANTS_SECTION
+                    )));
+                }
+                has_variants_section = true;
+
+                colors.variants = access.next_value::<Variants>()?.0;
+                continue;
+            }
+
+            // The extra section holds the colors defined by the user
+            if key == EXTRA_SECTION {
+                if has_extra_section {
+                    return Err(de::Error::custom(format!(
+                        "Section `{}` was already defined",
+                        EXTRA_SECTION
+                    )));
+                }
+                has_extra_section = true;
+
+                let extra_colors = access.next_value::<ExtraColors>()?;
+                for (color_name, color_hex) in extra_colors.0 {
+                    // Checks whether color_name is valid
+                    if !is_extra_color(&color_name) || !REGEX_EXTRA_COLOR_NAME.is_match(&color_name)
+                    {
+                        return Err(de::Error::custom(format!(
+                            "Invalid extra color name `{}`. Use letters, digits and underscores only, without clashing with the standard color names",
+                            color_name
+                        )));
+                    }
+
+                    insert_color(&mut colors, color_name, &color_hex).map_err(de::Error::custom)?;
+                }
+                continue;
+            }
+
+            // Metadata of the colorscheme
+            if key == META_SECTION {
+                if has_meta_section {
+                    return Err(de::Error::custom(format!(
+                        "Section `{}` was already defined",
+                        META_SECTION
+                    )));
+                }
+                has_meta_section = true;
+
+                colors.meta = access.next_value::<Meta>()?;
+                continue;
+            }
+
+            // Schema used by editors, irrelevant to the colorscheme
+            if key == SCHEMA_KEY {
+                access.next_value::<String>()?;
+                continue;
+            }
+
+            // Name of the parent colorscheme
+            if key == EXTENDS {
+                if colors.extends.is_some() {
+                    return Err(de::Error::custom(format!(
+                        "Key `{}` was already defined",
+                        EXTENDS
+                    )));
+                }
+
+                colors.extends = Some(access.next_value::<String>()?);
+                continue;
+            }
+
+            let color_hex = access.next_value::<String>()?;
+
             // Checks whether color_name is valid
-            if !COLOR_NAMES.contains(&color_name) {
+            if !COLOR_NAMES.contains(&key.as_str())
+                && !OPTIONAL_COLOR_NAMES.contains(&key.as_str())
+                && !BASE_COLOR_NAMES.contains(&key.as_str())
+            {
                 // TODO: print which are the valid color names
                 return Err(de::Error::custom(format!(
-                    "Invalid color name `{}`",
-                    color_name
+                    "Invalid color name `{}`. Custom colors go inside the `{}` section",
+                    key, EXTRA_SECTION
                 )));
             }
 
-            // Converts the hex string into an RGB struct
-            let color_rgb = RGB::new_from_hex(color_hex).map_err(|e| de::Error::custom(e))?;
+            insert_color(&mut colors, key, &color_hex).map_err(de::Error::custom)?;
+        }
 
-            colors.insert(color_name.to_owned(), color_rgb);
+        // A colorscheme that extends another one is completed once merged
+        // with its parent, and one with variants once a variant is chosen
+        if !self.is_variant && colors.extends.is_none() && colors.variants.is_empty() {
+            colors.complete().map_err(de::Error::custom)?;
         }
 
-        // Checks whether there are missing colors:
-        if colors.len() < COLOR_NAMES.len() {
-            for &color_name in &COLOR_NAMES {
-                if !colors.contains_key(color_name) {
-                    return Err(de::Error::custom(format!(
-                        "Missing required color `{}`",
-                        color_name
-                    )));
-                }
+        Ok(colors)
+    }
+}
+
+// Adds a color (or its expression) to the ColorTable, unless it was
+// already defined
+fn insert_color(
+    colors: &mut ColorTable,
+    color_name: String,
+    color_hex: &str,
+) -> Result<(), String> {
+    // Checks whether color_name was already present
+    if colors.contains_key(&color_name) || colors.expressions.contains_key(&color_name) {
+        return Err(format!("Color `{}` was already defined", color_name));
+    }
+
+    // Converts the hex string into an RGBA struct. Anything else is an
+    // expression, evaluated once all the colors are known
+    match RGBA::new_from_hex(color_hex) {
+        Ok(color_rgb) => {
+            colors.insert(color_name, color_rgb);
+        }
+        Err(_) => {
+            let expression = Expression::parse(color_hex).map_err(|e| {
+                format!("Invalid value of `{}`: `{}`. {}", color_name, color_hex, e)
+            })?;
+            colors.expressions.insert(color_name, expression);
+        }
+    }
+
+    Ok(())
+}
+
+// Entries of the extra section, in the same order as the file. Unlike
+// a HashMap, it keeps duplicated entries, so that they can be reported
+struct ExtraColors(Vec<(String, String)>);
+
+struct ExtraColorsVisitor;
+
+impl<'de> Visitor<'de> for ExtraColorsVisitor {
+    type Value = ExtraColors;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of extra colors")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+
+        while let Some(entry) = access.next_entry::<String, String>()? {
+            entries.push(entry);
+        }
+
+        Ok(ExtraColors(entries))
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtraColors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ExtraColorsVisitor)
+    }
+}
+
+// Variants of the variants section, in the same order as the file, so
+// that the first one can be used by default
+struct Variants(Vec<(String, ColorTable)>);
+
+struct VariantsVisitor;
+
+impl<'de> Visitor<'de> for VariantsVisitor {
+    type Value = Variants;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of colorscheme variants")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut variants: Vec<(String, ColorTable)> = Vec::new();
+
+        while let Some(name) = access.next_key::<String>()? {
+            // Variant names are used on the command line
+            if !REGEX_EXTRA_COLOR_NAME.is_match(&name) {
+                return Err(de::Error::custom(format!(
+                    "Invalid variant name `{}`. Use letters, digits and underscores only",
+                    name
+                )));
             }
+
+            if variants.iter().any(|(other, _)| *other == name) {
+                return Err(de::Error::custom(format!(
+                    "Variant `{}` was already defined",
+                    name
+                )));
+            }
+
+            let variant = access.next_value::<VariantTable>()?;
+            variants.push((name, variant.0));
         }
 
-        Ok(colors)
+        if variants.is_empty() {
+            return Err(de::Error::custom(format!(
+                "Section `{}` must define at least one variant",
+                VARIANTS_SECTION
+            )));
+        }
+
+        Ok(Variants(variants))
+    }
+}
+
+impl<'de> Deserialize<'de> for Variants {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(VariantsVisitor)
+    }
+}
+
+// Colors of a single variant
+struct VariantTable(ColorTable);
+
+impl<'de> Deserialize<'de> for VariantTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_map(ColorTableVisitor::new_variant())
+            .map(VariantTable)
     }
 }
 
@@ -110,6 +346,8 @@ impl<'de> Deserialize<'de> for ColorTable {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::colortable::meta::Variant;
+    use crate::colortable::rgb::{MixSpace, RGB};
     use serde_json;
 
     #[test]
@@ -139,4 +377,250 @@ mod tests {
         let colorscheme = serde_json::from_str::<ColorTable>(json);
         assert!(colorscheme.is_err());
     }
+
+    #[test]
+    fn test_base_palette() {
+        let json = r##"{ "base0D": "#7cafc2", "base17": "#a16946", "background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json);
+        assert!(colorscheme.is_ok());
+    }
+
+    #[test]
+    fn test_extra_colors() {
+        let json = r##"{ "extra": { "accent": "#ff8800", "comment": "#5c6370" }, "background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(colorscheme.contains_key("accent"));
+        assert!(colorscheme.contains_key("comment"));
+    }
+
+    #[test]
+    fn test_invalid_extra_colors() {
+        let required = r##""background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000""##;
+        let extra_sections = [
+            // Invalid hex
+            r##""extra": { "accent": "#ff888" }"##,
+            // Clashes with a standard color
+            r##""extra": { "background": "#ff8800" }"##,
+            // Not usable inside blueprints
+            r##""extra": { "my-accent": "#ff8800" }"##,
+            // Duplicated color
+            r##""extra": { "accent": "#ff8800", "accent": "#ff8800" }"##,
+            // Duplicated section
+            r##""extra": { "accent": "#ff8800" }, "extra": { "comment": "#ff8800" }"##,
+        ];
+
+        for extra_section in extra_sections {
+            let json = format!("{{ {}, {} }}", extra_section, required);
+            let colorscheme = serde_json::from_str::<ColorTable>(&json);
+            assert!(
+                colorscheme.is_err(),
+                "Should fail to parse {}",
+                extra_section
+            );
+        }
+    }
+
+    #[test]
+    fn test_extra_colors_are_optional() {
+        let json = r##"{ "extra": {}, "background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json);
+        assert!(colorscheme.is_ok());
+    }
+
+    #[test]
+    fn test_derived_optional_colors() {
+        let json = r##"{ "url": "#123456", "background": "#000000", "foreground": "#FFFFFF", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#0000FF", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#808080", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+
+        // Explicit values are kept
+        assert_eq!(colorscheme["url"], RGB(0x12, 0x34, 0x56));
+        assert!(!colorscheme.is_derived("url"));
+
+        // Missing ones are derived
+        assert_eq!(colorscheme["cursor_text"], RGB(0, 0, 0));
+        assert_eq!(colorscheme["active_border"], RGB(0, 0, 0xFF));
+        assert_eq!(colorscheme["inactive_border"], RGB(0x80, 0x80, 0x80));
+        assert_eq!(
+            colorscheme["selection_background"],
+            RGB(0xFF, 0xFF, 0xFF).mix_in(MixSpace::Srgb, 30, &RGB(0, 0, 0))
+        );
+        assert!(colorscheme.is_derived("selection_background"));
+    }
+
+    #[test]
+    fn test_extends() {
+        // Required colors can be left to the parent
+        let json =
+            r##"{ "extends": "parent", "background": "#FFFFFF", "color_01": "background" }"##;
+        let mut child = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert_eq!(child.extends.as_deref(), Some("parent"));
+
+        let json = r##"{ "background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let parent = serde_json::from_str::<ColorTable>(json).unwrap();
+
+        child.inherit(parent);
+        assert!(child.complete().is_ok());
+
+        // Overridden colors are kept, and optional ones are derived again
+        assert_eq!(child["background"], RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(child["cursor_text"], RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(child["foreground"], RGB(0, 0, 0));
+        assert_eq!(child["color_01"], RGB(0xFF, 0xFF, 0xFF));
+    }
+
+    #[test]
+    fn test_extends_expressions() {
+        // The parent's expressions follow the colors overridden by the child
+        let json = r##"{ "extends": "parent", "color_01": "#FF0000" }"##;
+        let mut child = serde_json::from_str::<ColorTable>(json).unwrap();
+
+        let json = r##"{ "background": "#000000", "foreground": "#FFFFFF", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "lighten(color_01, 15%)", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let parent = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert_eq!(parent["color_09"], RGB(0x26, 0x26, 0x26));
+
+        child.inherit(parent);
+        assert!(child.complete().is_ok());
+        assert_eq!(child["color_09"], RGB(0xFF, 0x4C, 0x4C));
+    }
+
+    #[test]
+    fn test_two_extends() {
+        let json = r##"{ "extends": "a", "extends": "b" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json);
+        assert!(colorscheme.is_err());
+    }
+
+    #[test]
+    fn test_expressions() {
+        let json = r##"{ "background": "#000000", "foreground": "#FFFFFF", "cursor": "foreground", "color_01": "#FF0000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "hue-rotate(color_10, 120deg)", "color_10": "darken(color_01, 25%)", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "mix(foreground, background, 25%)" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert_eq!(colorscheme["cursor"], RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(colorscheme["color_10"], RGB(0x80, 0, 0));
+        assert_eq!(colorscheme["color_09"], RGB(0, 0x80, 0));
+        assert_eq!(colorscheme["color_16"], RGB(0x40, 0x40, 0x40));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let json = r##"{ "background": "#000000", "foreground": "#FFFFFF", "cursor": "lighten(color_01, 10%)", "color_01": "color_02", "color_02": "cursor", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let error = serde_json::from_str::<ColorTable>(json).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Cyclic color references: `color_01` -> `color_02` -> `cursor` -> `color_01`"
+        ));
+
+        let json = r##"{ "background": "lighten(foregroud, 10%)" }"##;
+        let error = serde_json::from_str::<ColorTable>(json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unknown color `foregroud` in the value of `background` (column 9)"));
+
+        let json = r##"{ "background": "lighten(foreground 10%)" }"##;
+        let error = serde_json::from_str::<ColorTable>(json).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Invalid value of `background`: `lighten(foreground 10%)`. Expected `)` at column 20"
+        ));
+    }
+
+    #[test]
+    fn test_meta() {
+        let json = r##"{ "meta": { "author": "Someone", "variant": "light" }, "background": "#000000", "foreground": "#000000", "cursor": "#000000", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert_eq!(colorscheme.meta().author.as_deref(), Some("Someone"));
+        assert!(!colorscheme.contains_key("meta"));
+        assert!(colorscheme.extra_color_names().is_empty());
+
+        let json = r##"{ "meta": {}, "meta": {} }"##;
+        assert!(serde_json::from_str::<ColorTable>(json).is_err());
+    }
+
+    #[test]
+    fn test_variants() {
+        let json = r##"{ "foreground": "#FFFFFF", "cursor": "foreground", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000", "variants": { "dark": { "background": "#000000" }, "light": { "background": "#FFFFFF", "foreground": "#000000", "extra": { "accent": "foreground" } } } }"##;
+
+        // The first variant is the default one
+        let mut dark = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(dark.apply_variant(None).is_ok());
+        assert!(dark.complete().is_ok());
+        assert_eq!(dark.variant_name(), Some("dark"));
+        assert_eq!(dark.variant_names(), ["dark", "light"]);
+        assert_eq!(dark["cursor"], RGB(0xFF, 0xFF, 0xFF));
+
+        // Variants override the shared colors
+        let mut light = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(light.apply_variant(Some("light")).is_ok());
+        assert!(light.complete().is_ok());
+        assert_eq!(light["cursor"], RGB(0, 0, 0));
+        assert_eq!(light["accent"], RGB(0, 0, 0));
+
+        let mut unknown = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(unknown.apply_variant(Some("dim")).is_err());
+    }
+
+    #[test]
+    fn test_variants_meta() {
+        let json = r##"{ "meta": { "name": "Gruvbox", "author": "Someone", "variant": "dark" }, "foreground": "#FFFFFF", "cursor": "foreground", "color_01": "#000000", "color_02": "#000000", "color_03": "#000000", "color_04": "#000000", "color_05": "#000000", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000", "variants": { "dark": { "background": "#000000" }, "light": { "meta": { "name": "Gruvbox Light" }, "background": "#000000" } } }"##;
+
+        // The name of the variant wins over the shared meta section
+        let mut light = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(light.apply_variant(Some("light")).is_ok());
+        assert!(light.complete().is_ok());
+        assert_eq!(light.variant(), Variant::Light);
+
+        // The meta section of the variant is merged over the shared one
+        assert_eq!(light.meta().name.as_deref(), Some("Gruvbox Light"));
+        assert_eq!(light.meta().author.as_deref(), Some("Someone"));
+
+        let mut dark = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(dark.apply_variant(None).is_ok());
+        assert_eq!(dark.meta().name.as_deref(), Some("Gruvbox"));
+    }
+
+    #[test]
+    fn test_invalid_variants() {
+        let variants_sections = [
+            // No variants
+            r##"{ "variants": {} }"##,
+            // Duplicated variant
+            r##"{ "variants": { "dark": {}, "dark": {} } }"##,
+            // Not usable on the command line
+            r##"{ "variants": { "dark mode": {} } }"##,
+            // Nested sections
+            r##"{ "variants": { "dark": { "extends": "parent" } } }"##,
+            r##"{ "variants": { "dark": { "variants": {} } } }"##,
+        ];
+
+        for json in variants_sections {
+            let colorscheme = serde_json::from_str::<ColorTable>(json);
+            assert!(colorscheme.is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn test_schema_key() {
+        let json = r##"{ "$schema": "./colorscheme.schema.json", "extends": "parent" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert!(colorscheme.is_empty());
+    }
+
+    #[test]
+    fn test_css_colors() {
+        let json = r##"{ "extra": { "red": "#CC0000" }, "background": "rgb(40, 40, 40)", "foreground": "hsl(0 0% 100%)", "cursor": "#fff", "color_01": "red", "color_02": "rebeccapurple", "color_03": "hwb(0 100% 0%)", "color_04": "oklch(0 0 0)", "color_05": "lighten(rgba(0 0 0 / 50%), 100%)", "color_06": "#000000", "color_07": "#000000", "color_08": "#000000", "color_09": "#000000", "color_10": "#000000", "color_11": "#000000", "color_12": "#000000", "color_13": "#000000", "color_14": "#000000", "color_15": "#000000", "color_16": "#000000" }"##;
+        let colorscheme = serde_json::from_str::<ColorTable>(json).unwrap();
+        assert_eq!(colorscheme["background"], RGB(40, 40, 40));
+        assert_eq!(colorscheme["foreground"], RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(colorscheme["cursor"], RGB(0xFF, 0xFF, 0xFF));
+        // Colors of the colorscheme shadow the CSS named colors
+        assert_eq!(colorscheme["color_01"], RGB(0xCC, 0, 0));
+        assert_eq!(colorscheme["color_02"], RGB(0x66, 0x33, 0x99));
+        assert_eq!(colorscheme["color_03"], RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(colorscheme["color_04"], RGB(0, 0, 0));
+        assert_eq!(colorscheme["color_05"].rgb, RGB(0xFF, 0xFF, 0xFF));
+        assert_eq!(colorscheme["color_05"].alpha, 128);
+
+        let json = r##"{ "background": "rgb(300, 0, 0)" }"##;
+        let error = serde_json::from_str::<ColorTable>(json).unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Invalid value of `background`: `rgb(300, 0, 0)`. Invalid `rgb()` color `rgb(300, 0, 0)`. Invalid red `300`. Expected a number between 0 and 255 or a percentage at column 1"
+        ));
+    }
 }
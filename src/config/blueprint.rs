@@ -1,5 +1,30 @@
 pub const MIXED_COLOR_FIELD_SEPARATOR: &str = ":";
 
+// Filters follow the color inside a placeholder and transform it from
+// left to right, e.g. `{color_04 | lighten 10% | alpha 0.8 | fmt rgb}`.
+// The argument is separated from the name by spaces, or by a colon as in
+// `{background|alpha:80}` (where the opacity is in percent)
+pub const FILTER_SEPARATOR: &str = "|";
+pub const FILTER_ALPHA: &str = "alpha";
+// `{background:80:color_04|mix:oklab}` mixes the colors in OKLab,
+// whatever the `%mix-space` directive says
+pub const FILTER_MIX: &str = "mix";
+// `{color_01|fmt rgb}` writes the color with a preset of
+// `%color-format`, whatever the directive says
+pub const FILTER_FORMAT: &str = "fmt";
+pub const FILTER_LIGHTEN: &str = "lighten";
+pub const FILTER_DARKEN: &str = "darken";
+pub const FILTER_SATURATE: &str = "saturate";
+pub const FILTER_DESATURATE: &str = "desaturate";
+pub const FILTER_HUE_ROTATE: &str = "hue-rotate";
+pub const FILTER_INVERT: &str = "invert";
+pub const FILTER_GRAYSCALE: &str = "grayscale";
+// Replaces a background with a readable text color: the foreground when
+// its contrast ratio is at least CONTRAST_FG_RATIO, otherwise black or
+// white, whichever contrasts the most
+pub const FILTER_CONTRAST_FG: &str = "contrast_fg";
+pub const CONTRAST_FG_RATIO: f32 = 4.5;
+
 pub mod directive {
     // Each directive consists of "{PREFIX}{DIRECTIVE}{SEPARATOR} {VALUE}"
     pub const PREFIX: &str = "%";
@@ -8,8 +33,55 @@ pub mod directive {
     // DIRECTIVE
     pub const DIRECTIVE_COLOR_FORMAT: &str = "color-format";
     pub const DIRECTIVE_OUTPUT_DIRECTORY: &str = "output-directory";
+    pub const DIRECTIVE_MIX_SPACE: &str = "mix-space";
 
     // Color formats
     pub const HEX_6_DIGITS_W_HASHTAG: &'static str = "#6h";
     pub const HEX_6_DIGITS_WO_HASHTAG: &'static str = "6h";
+    pub const HEX_8_DIGITS_W_HASHTAG: &str = "#8h";
+    pub const HEX_8_DIGITS_WO_HASHTAG: &str = "8h";
+    pub const RGBA_FUNCTION: &str = "rgba";
+    pub const HEX_6_DIGITS_LOWER_W_HASHTAG: &str = "#6h-lower";
+    pub const HEX_6_DIGITS_LOWER_WO_HASHTAG: &str = "6h-lower";
+    pub const HEX_6_DIGITS_W_0X: &str = "0x6h";
+    pub const X11_RGB: &str = "x11";
+    pub const RGB_FUNCTION: &str = "rgb";
+    pub const HSL_FUNCTION: &str = "hsl";
+    pub const DECIMAL_COMMAS: &str = "decimal";
+    pub const DECIMAL_SEMICOLONS: &str = "sgr";
+    pub const FLOAT_CHANNELS: &str = "float";
+    pub const INTEGER: &str = "int";
+
+    // Templates of the color formats. Fields are written between braces,
+    // optionally followed by a notation, e.g. `{r:x}` (see
+    // colortable::color_format)
+    pub const COLOR_FORMAT_PRESETS: [(&str, &str); 15] = [
+        (HEX_6_DIGITS_W_HASHTAG, "#{r:X}{g:X}{b:X}"),
+        (HEX_6_DIGITS_WO_HASHTAG, "{r:X}{g:X}{b:X}"),
+        (HEX_8_DIGITS_W_HASHTAG, "#{r:X}{g:X}{b:X}{a:X}"),
+        (HEX_8_DIGITS_WO_HASHTAG, "{r:X}{g:X}{b:X}{a:X}"),
+        (RGBA_FUNCTION, "rgba({r}, {g}, {b}, {a:f})"),
+        (HEX_6_DIGITS_LOWER_W_HASHTAG, "#{r:x}{g:x}{b:x}"),
+        (HEX_6_DIGITS_LOWER_WO_HASHTAG, "{r:x}{g:x}{b:x}"),
+        // Alacritty
+        (HEX_6_DIGITS_W_0X, "0x{r:x}{g:x}{b:x}"),
+        // X11 resources and OSC escape sequences
+        (X11_RGB, "rgb:{r:x}/{g:x}/{b:x}"),
+        (RGB_FUNCTION, "rgb({r}, {g}, {b})"),
+        (HSL_FUNCTION, "hsl({h}, {s}%, {l}%)"),
+        (DECIMAL_COMMAS, "{r},{g},{b}"),
+        // SGR escape sequences, e.g. `\e[38;2;{color_04}m`
+        (DECIMAL_SEMICOLONS, "{r};{g};{b}"),
+        // Lua and Qt scripts
+        (FLOAT_CHANNELS, "{r:f}, {g:f}, {b:f}"),
+        (INTEGER, "{int}"),
+    ];
+
+    // Mix spaces of the composite colors
+    pub const MIX_SPACE_SRGB: &str = "srgb";
+    pub const MIX_SPACE_LINEAR_SRGB: &str = "linear-srgb";
+    pub const MIX_SPACE_OKLAB: &str = "oklab";
+    pub const MIX_SPACE_LAB: &str = "lab";
+    pub const MIX_SPACE_HSL: &str = "hsl";
+    pub const MIX_SPACE_HSV: &str = "hsv";
 }
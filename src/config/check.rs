@@ -0,0 +1,26 @@
+use super::colorscheme::*;
+
+// Minimum WCAG contrast ratio between an ANSI color and the background.
+// 3:1 is the WCAG AA threshold for large text and UI components
+pub const MIN_CONTRAST_RATIO: f32 = 3.0;
+
+// CIEDE2000 difference below which two ANSI colors look the same
+pub const DUPLICATE_DELTA_E: f32 = 1.0;
+
+// Normal ANSI colors and their bright counterparts
+pub const NORMAL_BRIGHT_PAIRS: [(&str, &str); 8] = [
+    (COLOR_01, COLOR_09),
+    (COLOR_02, COLOR_10),
+    (COLOR_03, COLOR_11),
+    (COLOR_04, COLOR_12),
+    (COLOR_05, COLOR_13),
+    (COLOR_06, COLOR_14),
+    (COLOR_07, COLOR_15),
+    (COLOR_08, COLOR_16),
+];
+
+// ANSI colors that are meant to blend with the background, and so are
+// exempt from the contrast check: black on dark colorschemes, white and
+// bright white on light ones
+pub const DARK_BLENDING_COLORS: [&str; 1] = [COLOR_01];
+pub const LIGHT_BLENDING_COLORS: [&str; 2] = [COLOR_08, COLOR_16];
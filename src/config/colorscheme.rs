@@ -1,6 +1,20 @@
 use serde::Deserialize;
 
-pub const FILE_EXTENSION: &str = "json";
+// Supported colorscheme file extensions
+pub const FILE_EXTENSION_JSON: &str = "json";
+pub const FILE_EXTENSION_JSON5: &str = "json5";
+pub const FILE_EXTENSION_TOML: &str = "toml";
+pub const FILE_EXTENSION_YAML: &str = "yaml";
+pub const FILE_EXTENSION_YML: &str = "yml";
+
+// Extensions are tried in this order when looking for a colorscheme
+pub const FILE_EXTENSIONS: [&str; 5] = [
+    FILE_EXTENSION_JSON,
+    FILE_EXTENSION_TOML,
+    FILE_EXTENSION_YAML,
+    FILE_EXTENSION_YML,
+    FILE_EXTENSION_JSON5,
+];
 
 pub const BACKGROUND: &str = "background";
 pub const FOREGROUND: &str = "foreground";
@@ -27,3 +41,115 @@ pub const COLOR_NAMES: [&str; 19] = [
     COLOR_07, COLOR_08, COLOR_09, COLOR_10, COLOR_11, COLOR_12, COLOR_13, COLOR_14, COLOR_15,
     COLOR_16,
 ];
+
+// The 16 ANSI colors, in order
+pub const ANSI_COLOR_NAMES: [&str; 16] = [
+    COLOR_01, COLOR_02, COLOR_03, COLOR_04, COLOR_05, COLOR_06, COLOR_07, COLOR_08, COLOR_09,
+    COLOR_10, COLOR_11, COLOR_12, COLOR_13, COLOR_14, COLOR_15, COLOR_16,
+];
+
+// Optional colors. When a colorscheme omits them, they're derived from
+// the required ones (see OPTIONAL_COLOR_DEFAULTS)
+pub const SELECTION_BACKGROUND: &str = "selection_background";
+pub const SELECTION_FOREGROUND: &str = "selection_foreground";
+pub const CURSOR_TEXT: &str = "cursor_text";
+pub const BOLD: &str = "bold";
+pub const URL: &str = "url";
+pub const ACTIVE_BORDER: &str = "active_border";
+pub const INACTIVE_BORDER: &str = "inactive_border";
+pub const VISUAL_BELL: &str = "visual_bell";
+
+pub const OPTIONAL_COLOR_NAMES: [&str; 8] = [
+    SELECTION_BACKGROUND,
+    SELECTION_FOREGROUND,
+    CURSOR_TEXT,
+    BOLD,
+    URL,
+    ACTIVE_BORDER,
+    INACTIVE_BORDER,
+    VISUAL_BELL,
+];
+
+// Default value of each optional color, as `(color, color1, amount,
+// color2)`: color1 weighted by amount/100 mixed with color2, just like
+// the `{color1:amount:color2}` blueprint placeholder. An amount of 100
+// is a plain copy of color1
+pub const OPTIONAL_COLOR_DEFAULTS: [(&str, &str, u8, &str); 8] = [
+    // The selection is a tint of the foreground over the background
+    (SELECTION_BACKGROUND, FOREGROUND, 30, BACKGROUND),
+    (SELECTION_FOREGROUND, FOREGROUND, 100, FOREGROUND),
+    // The text under the cursor looks like a hole in the cursor
+    (CURSOR_TEXT, BACKGROUND, 100, BACKGROUND),
+    (BOLD, FOREGROUND, 100, FOREGROUND),
+    // Blue
+    (URL, COLOR_05, 100, COLOR_05),
+    // Blue, just like the URLs, and bright black
+    (ACTIVE_BORDER, COLOR_05, 100, COLOR_05),
+    (INACTIVE_BORDER, COLOR_09, 100, COLOR_09),
+    // A subtle flash of the foreground over the background
+    (VISUAL_BELL, FOREGROUND, 20, BACKGROUND),
+];
+
+// Section holding the colors defined by the user (e.g. `accent`,
+// `comment`, ...). Any name made of letters, digits and underscores is
+// allowed, as long as it's not one of the names above
+pub const EXTRA_SECTION: &str = "extra";
+pub const EXTRA_COLOR_NAME_PATTERN: &str = r"^\w+$";
+
+// Values accepted for a color: 6 or 8 digits hex colors, with or without
+// hashtag, or expressions (references, function calls and CSS colors).
+// Expressions are only checked loosely, the parser reports the details.
+// Both patterns are valid in Rust and in JSON Schema (ECMA 262)
+pub const HEX_COLOR_PATTERN: &str = r"^\s*#?([0-9a-fA-F]{6}|[0-9a-fA-F]{8})\s*$";
+pub const COLOR_EXPRESSION_PATTERN: &str =
+    r"^\s*(#([0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})|[a-zA-Z_][\w-]*\s*(\(.*\))?)\s*$";
+
+// Name of the parent colorscheme. A colorscheme that extends another one
+// inherits all of its colors and only needs to override some of them
+pub const EXTENDS: &str = "extends";
+
+// URL or path of the JSON Schema of the file (see `chromasync schema`),
+// used by editors for validation and completion. It's ignored otherwise
+pub const SCHEMA_KEY: &str = "$schema";
+
+// Section holding named variants of the colorscheme (e.g. `dark` and
+// `light`). Each variant is a table of colors (plus its own `extra`
+// section) merged over the colors shared by all the variants
+pub const VARIANTS_SECTION: &str = "variants";
+
+// Section holding the optional metadata of the colorscheme (name,
+// author, variant, description, tags and license)
+pub const META_SECTION: &str = "meta";
+
+// Optional base16/base24 palette. Colorschemes imported from base16 or
+// base24 keep their original palette, so that blueprints can refer to it
+pub const BASE_00: &str = "base00";
+pub const BASE_01: &str = "base01";
+pub const BASE_02: &str = "base02";
+pub const BASE_03: &str = "base03";
+pub const BASE_04: &str = "base04";
+pub const BASE_05: &str = "base05";
+pub const BASE_06: &str = "base06";
+pub const BASE_07: &str = "base07";
+pub const BASE_08: &str = "base08";
+pub const BASE_09: &str = "base09";
+pub const BASE_0A: &str = "base0A";
+pub const BASE_0B: &str = "base0B";
+pub const BASE_0C: &str = "base0C";
+pub const BASE_0D: &str = "base0D";
+pub const BASE_0E: &str = "base0E";
+pub const BASE_0F: &str = "base0F";
+pub const BASE_10: &str = "base10";
+pub const BASE_11: &str = "base11";
+pub const BASE_12: &str = "base12";
+pub const BASE_13: &str = "base13";
+pub const BASE_14: &str = "base14";
+pub const BASE_15: &str = "base15";
+pub const BASE_16: &str = "base16";
+pub const BASE_17: &str = "base17";
+
+pub const BASE_COLOR_NAMES: [&str; 24] = [
+    BASE_00, BASE_01, BASE_02, BASE_03, BASE_04, BASE_05, BASE_06, BASE_07, BASE_08, BASE_09,
+    BASE_0A, BASE_0B, BASE_0C, BASE_0D, BASE_0E, BASE_0F, BASE_10, BASE_11, BASE_12, BASE_13,
+    BASE_14, BASE_15, BASE_16, BASE_17,
+];
@@ -48,6 +48,76 @@ pub static CACHE_BLUEPRINTS_DIR: Lazy<PathBuf> = Lazy::new(|| CACHE_DIR.join("bl
 pub static CURRENT_COLORSCHEME_FILE: Lazy<PathBuf> =
     Lazy::new(|| CACHE_DIR.join("current-colorscheme.json"));
 
+// Name of the current colorscheme (e.g. `catppuccin/mocha`)
+pub static CURRENT_COLORSCHEME_NAME_FILE: Lazy<PathBuf> =
+    Lazy::new(|| CACHE_DIR.join("current-colorscheme-name"));
+
+//////////////////
+// Search paths //
+//////////////////
+// Directory shared by a team (e.g. a mounted or synced folder), with the
+// same `colorschemes/` and `blueprints/` layout as CONFIG_DIR
+pub static SHARED_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    env::var("CHROMASYNC_SHARED_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+});
+
+// System-wide directories, from $XDG_DATA_DIRS (`/usr/local/share` and
+// `/usr/share` when unset) plus `/usr/share`. Relative paths are ignored
+pub static SYSTEM_DIRS: Lazy<Vec<PathBuf>> = Lazy::new(|| {
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or("/usr/local/share:/usr/share".to_string());
+
+    let dirs = data_dirs
+        .split(':')
+        .chain(["/usr/share"])
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join(info::APP_NAME));
+
+    unique(dirs)
+});
+
+// Directories searched for colorschemes, by decreasing priority: the user
+// directory, the shared one and the system ones. A colorscheme shadows
+// the ones with the same name in the following directories
+pub static COLORSCHEMES_DIRS: Lazy<Vec<PathBuf>> = Lazy::new(|| {
+    let dirs = [COLORSCHEMES_DIR.clone()]
+        .into_iter()
+        .chain(SHARED_DIR.iter().map(|dir| dir.join("colorschemes")))
+        .chain(SYSTEM_DIRS.iter().map(|dir| dir.join("colorschemes")));
+
+    unique(dirs)
+});
+
+// Directories searched for blueprints, by decreasing priority. The ones
+// installed by other plugins in CACHE_DIR come after the shared ones
+pub static BLUEPRINTS_DIRS: Lazy<Vec<PathBuf>> = Lazy::new(|| {
+    let dirs = [CONFIG_BLUEPRINTS_DIR.clone()]
+        .into_iter()
+        .chain(SHARED_DIR.iter().map(|dir| dir.join("blueprints")))
+        .chain([CACHE_BLUEPRINTS_DIR.clone()])
+        .chain(SYSTEM_DIRS.iter().map(|dir| dir.join("blueprints")));
+
+    unique(dirs)
+});
+
+// Removes the duplicated directories, keeping the first occurrence
+fn unique(dirs: impl Iterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut unique_dirs: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !unique_dirs.contains(&dir) {
+            unique_dirs.push(dir);
+        }
+    }
+
+    unique_dirs
+}
+
 // Builds all directories
 pub fn build_dirs() -> Result<(), Error> {
     use std::fs;
@@ -0,0 +1,75 @@
+use super::colorscheme::*;
+
+// Side, in pixels, of the thumbnail an image is scaled down to before
+// its colors are clustered
+pub const IMAGE_SIZE: u32 = 256;
+
+// Number of clusters the colors of an image are grouped into, and the
+// maximum number of k-means iterations
+pub const PALETTE_SIZE: usize = 16;
+pub const MAX_ITERATIONS: usize = 20;
+
+// Clusters with a lower OKLCH chroma are considered gray, and can't
+// provide the hue of an ANSI color
+pub const MIN_CLUSTER_CHROMA: f32 = 0.04;
+
+// Maximum distance, in degrees, between the hue of a cluster and the
+// reference hue of an ANSI color for the cluster to tint that color
+pub const MAX_HUE_DISTANCE: f32 = 45.0;
+
+// OKLCH lightness of the background of dark and light colorschemes,
+// when the palette doesn't provide one
+pub const DARK_BACKGROUND_LIGHTNESS: f32 = 0.2;
+pub const LIGHT_BACKGROUND_LIGHTNESS: f32 = 0.96;
+
+// Ranges the lightness of the dominant dark and light colors of an
+// image is clamped to before it becomes the background lightness
+pub const DARK_BACKGROUND_RANGE: (f32, f32) = (0.12, 0.3);
+pub const LIGHT_BACKGROUND_RANGE: (f32, f32) = (0.9, 0.98);
+
+// Clusters darker than this OKLCH lightness are dark colors, the others
+// light colors
+pub const DARK_LIGHTNESS_THRESHOLD: f32 = 0.5;
+
+// Maximum OKLCH chroma of the grays (background, foreground, black and
+// white), which are tinted with the dominant color
+pub const MAX_TINT_CHROMA: f32 = 0.03;
+
+// OKLCH chroma range of the ANSI colors
+pub const MIN_ACCENT_CHROMA: f32 = 0.08;
+pub const MAX_ACCENT_CHROMA: f32 = 0.2;
+
+// Minimum WCAG contrast ratios against the background. Bright colors
+// and bright black only need MIN_CONTRAST_RATIO (see config::check)
+pub const FOREGROUND_CONTRAST: f32 = 7.0;
+pub const ACCENT_CONTRAST: f32 = 4.5;
+
+// Number of tones of the OKLCH tonal ramps the colors are picked from.
+// Tone n has a lightness of n / (RAMP_SIZE - 1)
+pub const RAMP_SIZE: usize = 101;
+
+// Tones between a normal ANSI color and its bright counterpart
+pub const BRIGHT_TONE_STEP: isize = 7;
+
+// Normal and bright ANSI colors, paired with the OKLCH hue of their
+// reference color: red, green, yellow, blue, magenta and cyan
+pub const ACCENT_HUES: [(&str, &str, f32); 6] = [
+    (COLOR_02, COLOR_10, 29.0),
+    (COLOR_03, COLOR_11, 142.0),
+    (COLOR_04, COLOR_12, 100.0),
+    (COLOR_05, COLOR_13, 264.0),
+    (COLOR_06, COLOR_14, 328.0),
+    (COLOR_07, COLOR_15, 195.0),
+];
+
+// Position of red, green, yellow, blue, magenta and cyan on the hue
+// wheel, evenly spaced by ACCENT_HUE_SPACING degrees starting from red
+pub const ACCENT_WHEEL_POSITIONS: [f32; 6] = [0.0, 2.0, 1.0, 4.0, 5.0, 3.0];
+pub const ACCENT_HUE_SPACING: f32 = 60.0;
+
+// Harmonies of a seed color, as hue offsets from the seed. ANSI colors
+// on these hues keep the chroma of the seed, the others are muted
+pub const ANALOGOUS_OFFSETS: [f32; 3] = [-60.0, 0.0, 60.0];
+pub const TRIADIC_OFFSETS: [f32; 3] = [-120.0, 0.0, 120.0];
+pub const COMPLEMENTARY_OFFSETS: [f32; 2] = [0.0, 180.0];
+pub const MUTED_CHROMA_FACTOR: f32 = 0.6;
@@ -1,7 +1,9 @@
 #![allow(unused)]
 pub mod blueprint;
+pub mod check;
 pub mod colorscheme;
 pub mod environ;
+pub mod generate;
 pub mod preview;
 
 /////////////////
@@ -46,6 +46,12 @@ fn app() -> Result<(), Error> {
         Some(("load", args)) => subcommands::load(args),
         Some(("reload", args)) => subcommands::reload(args),
         Some(("preview", args)) => subcommands::preview(args),
+        Some(("import", args)) => subcommands::import(args),
+        Some(("generate", args)) => subcommands::generate(args),
+        Some(("export", args)) => subcommands::export(args),
+        Some(("check", args)) => subcommands::check(args),
+        Some(("schema", args)) => subcommands::schema(args),
+        Some(("toggle-variant", args)) => subcommands::toggle_variant(args),
         _ => unreachable!(),
     }?;
 
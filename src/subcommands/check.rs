@@ -0,0 +1,448 @@
+use crate::colortable::meta::Variant;
+use crate::colortable::ColorTable;
+use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
+use crate::config::check::{
+    DARK_BLENDING_COLORS, DUPLICATE_DELTA_E, LIGHT_BLENDING_COLORS, MIN_CONTRAST_RATIO,
+    NORMAL_BRIGHT_PAIRS,
+};
+use crate::config::colorscheme::{ANSI_COLOR_NAMES, BACKGROUND, VARIANTS_SECTION};
+use crate::logging::Error::{self, ColorschemeError, SystemError};
+use crate::util::{colorscheme_paths, read_file, search_colorscheme};
+use clap::ArgMatches;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+// Locations reported by the parsers: serde_json/serde_yaml append
+// "at line L column C", toml starts with "TOML parse error at line L,
+// column C" and json5 points to " --> L:C"
+static REGEX_TRAILING_LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^(.*) at line (\d+) column (\d+)$").unwrap());
+static REGEX_TOML_LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^TOML parse error at line (\d+), column (\d+)").unwrap());
+static REGEX_JSON5_LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*--> (\d+):(\d+)").unwrap());
+
+// Error raised while loading a parent colorscheme (see
+// ColorTable::from_file_path)
+static REGEX_PARENT_ERROR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^In `([^`]+)`\. (.*)$").unwrap());
+
+// Problem found in a colorscheme file, optionally with its line and
+// column
+struct Problem {
+    path: PathBuf,
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(
+                f,
+                "{}:{}:{}: {}",
+                self.path.display(),
+                line,
+                column,
+                self.message
+            ),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+pub fn check(args: &ArgMatches) -> Result<(), Error> {
+    // Colorschemes to check: either all of them or the given ones,
+    // by name or by path. Names that can't be found are reported like
+    // any other problem, without stopping the others from being checked
+    let paths: Vec<Result<PathBuf, Problem>> = if args.get_flag("all") {
+        colorscheme_paths()
+            .map_err(SystemError)?
+            .into_iter()
+            .map(Ok)
+            .collect()
+    } else {
+        args.get_many::<String>("colorscheme")
+            .unwrap_or_default()
+            .map(|name| match Path::new(name).is_file() {
+                true => Ok(PathBuf::from(name)),
+                false => search_colorscheme(name).map_err(|message| Problem {
+                    path: PathBuf::from(name),
+                    location: None,
+                    message,
+                }),
+            })
+            .collect()
+    };
+
+    let checked = paths.len();
+    let mut problems = 0;
+    let mut invalid_colorschemes = 0;
+    for path in paths {
+        let found = match path {
+            Ok(path) => check_colorscheme(&path),
+            Err(problem) => vec![problem],
+        };
+        for problem in &found {
+            println!("{}", problem);
+        }
+
+        problems += found.len();
+        invalid_colorschemes += (!found.is_empty()) as usize;
+    }
+
+    if problems > 0 {
+        return Err(ColorschemeError(format!(
+            "Found {} problem{} in {} of {} colorschemes",
+            problems,
+            if problems == 1 { "" } else { "s" },
+            invalid_colorschemes,
+            checked
+        )));
+    }
+
+    println!("Checked {} colorschemes, no problems found", checked);
+
+    Ok(())
+}
+
+fn check_colorscheme(path: &Path) -> Vec<Problem> {
+    let path = &path.to_path_buf();
+    let colors = match ColorTable::from_file_path(path) {
+        Ok(colors) => colors,
+        Err(e) => return vec![parse_problem(path, &e)],
+    };
+
+    // The first variant is the default one, the others are checked too
+    let mut problems = lint_problems(path, &colors);
+    for variant in colors.variant_names().iter().skip(1) {
+        match ColorTable::from_file_path_with_variant(path, Some(variant)) {
+            Ok(colors) => problems.extend(lint_problems(path, &colors)),
+            Err(e) => problems.push(parse_problem(path, &e)),
+        }
+    }
+
+    problems
+}
+
+// Lints a colorscheme, reporting each problem in the file that defines
+// the color, which can be a parent colorscheme
+fn lint_problems(path: &Path, colors: &ColorTable) -> Vec<Problem> {
+    let mut contents: HashMap<PathBuf, Option<String>> = HashMap::new();
+    lint(colors)
+        .into_iter()
+        .map(|(name, message)| {
+            let path = colors
+                .origin(name)
+                .map(Path::to_path_buf)
+                .unwrap_or(path.to_path_buf());
+            let content = contents
+                .entry(path.clone())
+                .or_insert_with(|| read_file(&path).ok());
+
+            // Colors of a variant are looked up after its name
+            let variant = colors.variant_name();
+            let location = content.as_deref().and_then(|content| {
+                let variant_line = variant.and_then(|variant| locate_variant(content, variant));
+                match variant_line {
+                    Some(variant_line) => locate_key_after(content, name, variant_line),
+                    None => locate_key(content, name, 1),
+                }
+            });
+            let message = match variant {
+                Some(variant) => format!("In variant `{}`, {}", variant, message),
+                None => message,
+            };
+
+            Problem {
+                path,
+                location,
+                message,
+            }
+        })
+        .collect()
+}
+
+// Converts an error raised while loading the colorscheme into a Problem,
+// pointing to the key the error is about when possible, or to the
+// location reported by the parser otherwise
+fn parse_problem(path: &Path, error: &str) -> Problem {
+    // Errors of a parent colorscheme are reported in the parent file
+    let (path, error) = match REGEX_PARENT_ERROR.captures(error) {
+        Some(caps) => (PathBuf::from(&caps[1]), caps[2].to_string()),
+        None => (path.to_path_buf(), error.to_string()),
+    };
+
+    let (message, parser_location) = split_location(&error);
+
+    let key_location = read_file(&path).ok().and_then(|content| {
+        let key = error_key(&message)?;
+        // Duplicated keys are reported where they're defined again
+        let occurrence = if message.contains("already defined") {
+            2
+        } else {
+            1
+        };
+
+        locate_key(&content, &key, occurrence).or(locate_key(&content, &key, 1))
+    });
+
+    Problem {
+        path,
+        location: key_location.or(parser_location),
+        message,
+    }
+}
+
+// Splits the location added by the parsers from the error message
+fn split_location(error: &str) -> (String, Option<(usize, usize)>) {
+    let location = |line: &str, column: &str| Some((line.parse().ok()?, column.parse().ok()?));
+
+    if let Some(caps) = REGEX_TRAILING_LOCATION.captures(error) {
+        return (caps[1].to_string(), location(&caps[2], &caps[3]));
+    }
+
+    // toml and json5 print a snippet of the file, followed by the
+    // message. The snippet is dropped
+    let snippet_message = || {
+        error
+            .lines()
+            .skip_while(|line| !line.trim_start().starts_with('|') || !line.contains('^'))
+            .skip(1)
+            .map(|line| {
+                line.trim()
+                    .trim_start_matches("= ")
+                    .trim_start_matches('|')
+                    .trim()
+            })
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    if let Some(caps) = REGEX_TOML_LOCATION.captures(error) {
+        return (snippet_message(), location(&caps[1], &caps[2]));
+    }
+    if let Some(caps) = REGEX_JSON5_LOCATION.captures(error) {
+        return (snippet_message(), location(&caps[1], &caps[2]));
+    }
+
+    (error.trim().to_string(), None)
+}
+
+// Color (or section) an error is about, e.g. `foo` in "Invalid color
+// name `foo`"
+fn error_key(message: &str) -> Option<String> {
+    // Unknown references are reported in the color that uses them
+    let message = match message.split_once("in the value of ") {
+        Some((_, rest)) => rest,
+        None => message,
+    };
+
+    message.split('`').nth(1).map(str::to_string)
+}
+
+// Line and column (starting from 1) of the nth occurrence of a key,
+// whether written as `"key":`, `key:` or `key =`
+fn locate_key(content: &str, key: &str, occurrence: usize) -> Option<(usize, usize)> {
+    let regex = Regex::new(&format!(
+        r#"(^|[\s{{,\[])(["']?{}["']?\s*[:=])"#,
+        regex::escape(key)
+    ))
+    .ok()?;
+
+    let mut occurrences = content.lines().enumerate().flat_map(|(index, line)| {
+        regex.captures_iter(line).map(move |caps| {
+            let column = line[..caps.get(2).unwrap().start()].chars().count() + 1;
+            (index + 1, column)
+        })
+    });
+
+    occurrences.nth(occurrence - 1)
+}
+
+// First occurrence of a key after a line, or before it when the key
+// isn't defined again (e.g. colors shared by all the variants)
+fn locate_key_after(content: &str, key: &str, line: usize) -> Option<(usize, usize)> {
+    let mut occurrence = 1;
+    let first = locate_key(content, key, 1)?;
+
+    while let Some(location) = locate_key(content, key, occurrence) {
+        if location.0 > line {
+            return Some(location);
+        }
+        occurrence += 1;
+    }
+
+    Some(first)
+}
+
+// Line where a variant starts, whether declared as `[variants.name]`,
+// `"name":` or `name:`
+fn locate_variant(content: &str, variant: &str) -> Option<usize> {
+    let header = format!("{}.{}]", VARIANTS_SECTION, variant);
+    let variants = locate_key(content, VARIANTS_SECTION, 1).map_or(0, |(line, _)| line);
+
+    content
+        .lines()
+        .position(|line| line.contains(&header))
+        .map(|index| index + 1)
+        .or(locate_key_after(content, variant, variants).map(|(line, _)| line))
+}
+
+// Lints a valid colorscheme. Returns the problems found, each with the
+// name of the color it's about
+fn lint(colors: &ColorTable) -> Vec<(&'static str, String)> {
+    let mut problems = Vec::new();
+    let hex = |name: &str| colors[name].format(HEX_6_DIGITS_W_HASHTAG).unwrap();
+
+    // Several ANSI colors with the same value, or too close to be told
+    // apart. A normal color can be reused as bright one though
+    for (i, &first) in ANSI_COLOR_NAMES.iter().enumerate() {
+        for &second in &ANSI_COLOR_NAMES[i + 1..] {
+            if NORMAL_BRIGHT_PAIRS.contains(&(first, second)) {
+                continue;
+            }
+
+            let delta_e = colors[first].delta_e(&colors[second]);
+            if colors[first] == colors[second] {
+                problems.push((
+                    second,
+                    format!(
+                        "Duplicate color: `{}` has the same value as `{}` ({})",
+                        second,
+                        first,
+                        hex(first)
+                    ),
+                ));
+            } else if delta_e < DUPLICATE_DELTA_E {
+                problems.push((
+                    second,
+                    format!(
+                        "Duplicate color: `{}` ({}) looks the same as `{}` ({}), delta E {:.2}",
+                        second,
+                        hex(second),
+                        first,
+                        hex(first),
+                        delta_e
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Bright colors darker than the normal ones
+    for (normal, bright) in NORMAL_BRIGHT_PAIRS {
+        if colors[bright].relative_luminance() < colors[normal].relative_luminance() {
+            problems.push((
+                bright,
+                format!(
+                    "Bright color `{}` ({}) is darker than its normal counterpart `{}` ({})",
+                    bright,
+                    hex(bright),
+                    normal,
+                    hex(normal)
+                ),
+            ));
+        }
+    }
+
+    // ANSI colors that can't be read on the background
+    let blending_colors: &[&str] = match colors.variant() {
+        Variant::Dark => &DARK_BLENDING_COLORS,
+        Variant::Light => &LIGHT_BLENDING_COLORS,
+    };
+    let background = &colors[BACKGROUND];
+    for name in ANSI_COLOR_NAMES {
+        if blending_colors.contains(&name) {
+            continue;
+        }
+
+        let contrast_ratio = colors[name].contrast_ratio(background);
+        if contrast_ratio < MIN_CONTRAST_RATIO {
+            problems.push((
+                name,
+                format!(
+                    "`{}` ({}) is hard to read on the background ({}): contrast ratio is {:.2}:1, at least {}:1 is needed",
+                    name,
+                    hex(name),
+                    hex(BACKGROUND),
+                    contrast_ratio,
+                    MIN_CONTRAST_RATIO
+                ),
+            ));
+        }
+    }
+
+    problems
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_keys() {
+        let json = "{\n  \"background\": \"#000000\",\n  \"cursor\": \"#000000\", \"cursor\": \"#FFFFFF\"\n}";
+        assert_eq!(locate_key(json, "background", 1), Some((2, 3)));
+        assert_eq!(locate_key(json, "cursor", 2), Some((3, 24)));
+        assert_eq!(locate_key(json, "foreground", 1), None);
+
+        let toml = "background = \"#000000\"\n[extra]\naccent=\"#FF8800\"";
+        assert_eq!(locate_key(toml, "accent", 1), Some((3, 1)));
+
+        let yaml = "meta:\n  name: background\nbackground: \"#000000\"";
+        assert_eq!(locate_key(yaml, "background", 1), Some((3, 1)));
+    }
+
+    #[test]
+    fn parser_locations() {
+        assert_eq!(
+            split_location("Invalid color name `foo` at line 4 column 1"),
+            ("Invalid color name `foo`".to_string(), Some((4, 1)))
+        );
+        assert_eq!(
+            split_location("TOML parse error at line 1, column 22\n  |\n1 | background = \"#000000\n  |                      ^\ninvalid basic string\n"),
+            ("invalid basic string".to_string(), Some((1, 22)))
+        );
+        assert_eq!(
+            split_location(" --> 3:15\n  |\n3 |   foo: \"#fff\",,\n  |               ^---\n  |\n  = expected identifier or string"),
+            ("expected identifier or string".to_string(), Some((3, 15)))
+        );
+    }
+
+    #[test]
+    fn error_keys() {
+        assert_eq!(
+            error_key("Invalid color name `foo`. Custom colors go inside the `extra` section"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            error_key("Unknown color `redd` in the value of `color_01` (column 9)"),
+            Some("color_01".to_string())
+        );
+    }
+
+    #[test]
+    fn lints() {
+        let json = r##"{ "background": "#000000", "foreground": "#FFFFFF", "cursor": "#FFFFFF", "color_01": "#000000", "color_02": "#FF5555", "color_03": "#55FF55", "color_04": "#FFFF55", "color_05": "#5555FF", "color_06": "#FF55FF", "color_07": "#55FFFF", "color_08": "#BBBBBB", "color_09": "#777777", "color_10": "#FF5555", "color_11": "#22AA22", "color_12": "#FFFF55", "color_13": "#111166", "color_14": "#FF77FF", "color_15": "#77FFFF", "color_16": "#FFFFFF" }"##;
+        let colors = serde_json::from_str::<ColorTable>(json).unwrap();
+
+        let problems: Vec<&str> = lint(&colors).into_iter().map(|(name, _)| name).collect();
+        // `color_11` is darker than `color_03`, `color_13` is darker than
+        // `color_05` and unreadable. `color_01` is black on a dark
+        // background, `color_10` reuses `color_02`
+        assert_eq!(problems, vec!["color_11", "color_13", "color_13"]);
+
+        // `color_07` can't be told apart from `color_06`
+        let json = json.replace(r##""color_07": "#55FFFF""##, r##""color_07": "#FF56FF""##);
+        let colors = serde_json::from_str::<ColorTable>(&json).unwrap();
+        let problems = lint(&colors);
+        assert_eq!(problems[0].0, "color_07");
+        assert!(problems[0].1.contains("looks the same as `color_06`"));
+    }
+}
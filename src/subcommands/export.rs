@@ -0,0 +1,32 @@
+use crate::colortable::export::Target;
+use crate::colortable::ColorTable;
+use crate::logging::Error::{self, ColorschemeError, SystemError};
+use crate::util::search_colorscheme;
+use clap::ArgMatches;
+use std::fs;
+
+pub fn export(args: &ArgMatches) -> Result<(), Error> {
+    // Complete file path
+    let colorscheme_name = args.get_one::<String>("colorscheme").unwrap();
+    let path = search_colorscheme(colorscheme_name).map_err(ColorschemeError)?;
+
+    // Loads the colorscheme
+    let variant = args.get_one::<String>("variant").map(String::as_str);
+    let colors =
+        ColorTable::from_file_path_with_variant(&path, variant).map_err(ColorschemeError)?;
+
+    // Serializes the colorscheme
+    let target = args.get_one::<Target>("to").unwrap();
+    let exported = target
+        .export(colorscheme_name, &colors)
+        .map_err(|e| ColorschemeError(format!("While exporting `{}`. {}", colorscheme_name, e)))?;
+
+    // Writes either to a file or to stdout
+    match args.get_one::<String>("output") {
+        Some(output) => fs::write(output, exported)
+            .map_err(|e| SystemError(format!("Can't write `{}`. {}", output, e)))?,
+        None => print!("{}", exported),
+    }
+
+    Ok(())
+}
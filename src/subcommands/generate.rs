@@ -0,0 +1,92 @@
+use super::import::save_colorscheme;
+use crate::color_test_table::print_color_test_table;
+use crate::colortable::generate::image::palette_from_image;
+use crate::colortable::generate::seed::{palette_from_seed, Harmony};
+use crate::colortable::generate::Palette;
+use crate::colortable::import::slugify;
+use crate::colortable::meta::{Meta, Variant};
+use crate::colortable::rgb::RGB;
+use crate::config::blueprint::directive::HEX_6_DIGITS_WO_HASHTAG;
+use crate::logging::Error::{self, ColorschemeError, InvalidCommandLineArgument, SystemError};
+use clap::{ArgMatches, ValueEnum};
+use std::path::PathBuf;
+
+pub fn generate(args: &ArgMatches) -> Result<(), Error> {
+    let force = args.get_flag("force");
+    let variant = match args.get_flag("light") {
+        true => Variant::Light,
+        false => Variant::Dark,
+    };
+
+    // Either the colors of an image or a seed color
+    let (palette, default_name, description) = match args.get_one::<String>("seed") {
+        Some(seed) => from_seed(args, seed)?,
+        None => from_image(args)?,
+    };
+
+    let name = args
+        .get_one::<String>("name")
+        .cloned()
+        .or(default_name)
+        .ok_or(InvalidCommandLineArgument(
+            "Can't find a name for the generated colorscheme. Use `--name`".to_string(),
+        ))?;
+    let meta = Meta {
+        name: Some(name.clone()),
+        variant: Some(variant),
+        description: Some(description),
+        tags: vec!["generated".to_string()],
+        ..Meta::default()
+    };
+
+    let colors = palette
+        .to_colortable(variant, meta)
+        .map_err(|e| ColorschemeError(format!("While generating `{}`. {}", name, e)))?;
+    let path = save_colorscheme(&name, &colors, force)?;
+
+    print_color_test_table(&colors);
+    println!("Generated `{}`", path.display());
+
+    Ok(())
+}
+
+// Palette, default name and description of a colorscheme generated from
+// an image. The default name is the image name
+fn from_image(args: &ArgMatches) -> Result<(Palette, Option<String>, String), Error> {
+    let image = PathBuf::from(args.get_one::<String>("from-image").unwrap());
+
+    let palette = palette_from_image(&image)
+        .map_err(|e| SystemError(format!("Can't read `{}`. {}", image.display(), e)))?;
+
+    let name = image
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(slugify)
+        .filter(|name| !name.is_empty());
+    let description = format!(
+        "Generated from `{}`",
+        image.file_name().unwrap_or_default().to_string_lossy()
+    );
+
+    Ok((palette, name, description))
+}
+
+// Palette, default name and description of a colorscheme generated
+// around a seed color. The default name is the seed and the harmony,
+// e.g. `4a90e2-analogous`
+fn from_seed(args: &ArgMatches, seed: &str) -> Result<(Palette, Option<String>, String), Error> {
+    let seed = RGB::new_from_hex(seed).map_err(InvalidCommandLineArgument)?;
+    let harmony = args
+        .get_one::<Harmony>("harmony")
+        .copied()
+        .unwrap_or_default();
+    let harmony_name = harmony.to_possible_value().unwrap().get_name().to_string();
+
+    let hex = seed
+        .format(HEX_6_DIGITS_WO_HASHTAG)
+        .map_err(InvalidCommandLineArgument)?;
+    let name = format!("{}-{}", hex.to_lowercase(), harmony_name);
+    let description = format!("Generated around `#{}`, {} harmony", hex, harmony_name);
+
+    Ok((palette_from_seed(&seed, harmony), Some(name), description))
+}
@@ -0,0 +1,213 @@
+use crate::colortable::import::{slugify, ImportReport, ImportedColorscheme, Source};
+use crate::colortable::ColorTable;
+use crate::config::colorscheme::FILE_EXTENSION_JSON;
+use crate::config::environ::COLORSCHEMES_DIR;
+use crate::logging::log_as_warning;
+use crate::logging::Error::{self, ColorschemeError, InvalidCommandLineArgument, SystemError};
+use crate::util::{read_file, search_colorscheme, validate_colorscheme_name};
+use clap::ArgMatches;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+// Reads the colorscheme from stdin instead of a file
+const STDIN: &str = "-";
+
+pub fn import(args: &ArgMatches) -> Result<(), Error> {
+    let file = PathBuf::from(args.get_one::<String>("file").unwrap());
+    let force = args.get_flag("force");
+
+    // Reads the file content
+    let content = if file.as_os_str() == STDIN {
+        read_stdin()?
+    } else {
+        read_file(&file)
+            .map_err(|e| SystemError(format!("Can't read `{}`. {}", file.display(), e)))?
+    };
+
+    // Source format: either the one specified by the user or the
+    // detected one
+    let source = match args.get_one::<Source>("from") {
+        Some(source) => *source,
+        None => Source::detect(&file, &content).map_err(InvalidCommandLineArgument)?,
+    };
+
+    // Converts the colorschemes
+    let colorschemes = source
+        .import(&content)
+        .map_err(|e| ColorschemeError(format!("While importing `{}`. {}", file.display(), e)))?;
+
+    if colorschemes.is_empty() {
+        return Err(ColorschemeError(format!(
+            "`{}` holds no colorscheme",
+            file.display()
+        )));
+    }
+
+    // A single name can't be given to several colorschemes
+    let several = colorschemes.len() > 1;
+    if several && args.get_one::<String>("name").is_some() {
+        return Err(InvalidCommandLineArgument(format!(
+            "`{}` holds {} colorschemes, `--name` can't be used",
+            file.display(),
+            colorschemes.len()
+        )));
+    }
+
+    // Every name is checked before writing anything, so that a clash
+    // doesn't leave the file half imported
+    let mut names: Vec<String> = Vec::new();
+    for (index, imported) in colorschemes.iter().enumerate() {
+        let name = colorscheme_name(args, &file, imported, several.then_some(index))?;
+        check_colorscheme_name(&name, force)?;
+
+        if names.contains(&name) {
+            return Err(ColorschemeError(format!(
+                "`{}` holds several colorschemes named `{}`",
+                file.display(),
+                name
+            )));
+        }
+        names.push(name);
+    }
+
+    for (name, imported) in names.iter().zip(&colorschemes) {
+        let path = write_colorscheme(name, &imported.colors)?;
+
+        print_report(&imported.report);
+        println!("Imported `{}` as `{}`", file.display(), path.display());
+    }
+
+    Ok(())
+}
+
+// Name of an imported colorscheme: either the one specified by the
+// user, the one declared inside the file or the file name. When a file
+// holds several unnamed colorschemes, their index is appended
+fn colorscheme_name(
+    args: &ArgMatches,
+    file: &Path,
+    imported: &ImportedColorscheme,
+    index: Option<usize>,
+) -> Result<String, Error> {
+    if let Some(name) = args.get_one::<String>("name") {
+        return Ok(name.clone());
+    }
+
+    if let Some(name) = imported.name.as_deref().map(slugify) {
+        if !name.is_empty() {
+            return Ok(name);
+        }
+    }
+
+    file.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(slugify)
+        .filter(|name| !name.is_empty())
+        .map(|name| match index {
+            Some(index) => format!("{}-{}", name, index + 1),
+            None => name,
+        })
+        .ok_or(InvalidCommandLineArgument(format!(
+            "Can't find a name for the colorscheme imported from `{}`. Use `--name`",
+            file.display()
+        )))
+}
+
+// Reads the whole stdin, e.g. the output of `xrdb -query`
+fn read_stdin() -> Result<String, Error> {
+    let mut content = String::new();
+    io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| SystemError(format!("Can't read from stdin. {}", e)))?;
+
+    Ok(content)
+}
+
+// Warns about the keys that were dropped, the colors that had to be
+// derived and anything else noted during the conversion
+fn print_report(report: &ImportReport) {
+    if !report.dropped.is_empty() {
+        log_as_warning(ColorschemeError(format!(
+            "Dropped keys without a {} counterpart: `{}`",
+            crate::config::info::APP_NAME,
+            report.dropped.join("`, `")
+        )));
+    }
+
+    for (color, source) in &report.derived {
+        log_as_warning(ColorschemeError(format!(
+            "Missing color `{}`. Derived from `{}`",
+            color, source
+        )));
+    }
+
+    for note in &report.notes {
+        log_as_warning(ColorschemeError(note.clone()));
+    }
+}
+
+// Stores a colorscheme inside COLORSCHEMES_DIR. Namespaced names such as
+// `catppuccin/mocha` are stored inside subdirectories
+pub(super) fn save_colorscheme(
+    name: &str,
+    colors: &ColorTable,
+    force: bool,
+) -> Result<PathBuf, Error> {
+    check_colorscheme_name(name, force)?;
+    write_colorscheme(name, colors)
+}
+
+// Checks that a name stays inside COLORSCHEMES_DIR and, unless `force`
+// is set, that it doesn't shadow an existing colorscheme
+fn check_colorscheme_name(name: &str, force: bool) -> Result<(), Error> {
+    validate_colorscheme_name(name).map_err(InvalidCommandLineArgument)?;
+
+    if let Ok(path) = search_colorscheme(name) {
+        if !force {
+            return Err(ColorschemeError(format!(
+                "Colorscheme `{}` already exists (`{}`). Use `--force` to overwrite it",
+                name,
+                path.display()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn write_colorscheme(name: &str, colors: &ColorTable) -> Result<PathBuf, Error> {
+    let path = COLORSCHEMES_DIR.join(format!("{}.{}", name, FILE_EXTENSION_JSON));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| SystemError(format!("Can't create `{}`. {}", dir.display(), e)))?;
+    }
+
+    colors
+        .to_file_path(&path)
+        .map_err(|e| SystemError(format!("Can't write `{}`. {}", path.display(), e)))?;
+
+    Ok(path)
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_names() {
+        // Names can't point outside of COLORSCHEMES_DIR
+        let colors = ColorTable::with_capacity(0);
+        for name in ["", "../../x", "/tmp/x", "catppuccin/../../x"] {
+            assert!(
+                matches!(
+                    save_colorscheme(name, &colors, true),
+                    Err(InvalidCommandLineArgument(_))
+                ),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -1,11 +1,16 @@
+use crate::colortable::meta::{Meta, Variant};
 use crate::colortable::rgb::RGB;
 use crate::colortable::ColorTable;
 use crate::config::colorscheme;
-use crate::config::environ::COLORSCHEMES_DIR;
-use crate::logging::{Error, Error::SystemError};
-use crate::util::print_with_custom_colors;
+use crate::config::info;
+use crate::logging::log_as_warning;
+use crate::logging::Error::{self, ColorschemeError, InvalidCommandLineArgument, SystemError};
+use crate::util::{
+    colorscheme_name, colorscheme_paths, colorscheme_source, print_with_custom_colors,
+    validate_colorscheme_name,
+};
 use clap::ArgMatches;
-use std::fs::read_dir;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 struct ColorschemeInfo {
@@ -14,27 +19,21 @@ struct ColorschemeInfo {
     foreground: RGB,
     background_luminance: f32,
     contrast: f32,
+    variant: Variant,
+    variant_names: Vec<String>,
+    meta: Meta,
+    // Directory of the search paths the colorscheme comes from
+    source: String,
 }
 
 impl ColorschemeInfo {
     fn new(path: &Path) -> Result<Self, String> {
-        // Extracts the file name
-        let filename = path
-            .file_stem()
-            .ok_or(format!("Can't extract file name of `{}`", path.display()))?
-            .to_str()
-            .ok_or(format!(
-                "Can't convert filename of `{}` to str",
-                path.display()
-            ))?
-            .to_string();
-
         // Loads the colorscheme
         let colortable = ColorTable::from_file_path(&path.to_path_buf())?;
 
         // Retrieves the bg/fg colors
-        let background = colortable.get(colorscheme::BACKGROUND).unwrap().clone();
-        let foreground = colortable.get(colorscheme::FOREGROUND).unwrap().clone();
+        let background = colortable.get(colorscheme::BACKGROUND).unwrap().rgb.clone();
+        let foreground = colortable.get(colorscheme::FOREGROUND).unwrap().rgb.clone();
 
         // Evaluates luminance and contrast
         let background_luminance = background.luminance();
@@ -42,13 +41,34 @@ impl ColorschemeInfo {
         let contrast = (foreground_luminance - background_luminance).abs();
 
         Ok(Self {
-            name: filename,
+            name: colorscheme_name(path),
             background,
             foreground,
             background_luminance,
             contrast,
+            variant: colortable.variant(),
+            variant_names: colortable.variant_names().to_vec(),
+            meta: colortable.meta().clone(),
+            source: colorscheme_source(path)
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
         })
     }
+
+    // Value of an optional column: a metadata field or the source
+    fn column(&self, column: &str) -> String {
+        match column {
+            "name" => self.meta.name.clone().unwrap_or_default(),
+            "author" => self.meta.author.clone().unwrap_or_default(),
+            "variant" => self.variant.to_string(),
+            "variants" => self.variant_names.join(", "),
+            "description" => self.meta.description.clone().unwrap_or_default(),
+            "tags" => self.meta.tags.join(", "),
+            "license" => self.meta.license.clone().unwrap_or_default(),
+            "source" => self.source.clone(),
+            _ => unreachable!(),
+        }
+    }
 }
 
 pub fn list(args: &ArgMatches) -> Result<(), Error> {
@@ -56,27 +76,62 @@ pub fn list(args: &ArgMatches) -> Result<(), Error> {
     let dark_only: bool = *args.get_one::<bool>("dark").ok_or(false).unwrap();
     let light_only: bool = *args.get_one::<bool>("light").ok_or(false).unwrap();
 
+    // Lists only colorschemes with all the given tags
+    let tags: Vec<&String> = args.get_many::<String>("tag").unwrap_or_default().collect();
+
+    // Optional columns to show: the chosen metadata, then the source
+    let mut columns: Vec<&str> = args
+        .get_many::<String>("meta")
+        .unwrap_or_default()
+        .map(String::as_str)
+        .collect();
+    if args.get_flag("source") {
+        columns.push("source");
+    }
+
+    // Lists only the colorschemes inside a directory (e.g. `catppuccin`)
+    let dir = args
+        .get_one::<String>("dir")
+        .map(|dir| dir.trim_end_matches('/'));
+    if let Some(dir) = dir {
+        validate_colorscheme_name(dir).map_err(InvalidCommandLineArgument)?;
+    }
+
     // Reads files contained in COLORSCHEMES_DIR and converts them
     // into ColorschemeInfo structs
-    let mut colorscheme_infos: Vec<_> = read_dir(&*COLORSCHEMES_DIR)
-        .map_err(|e| SystemError(e.to_string()))?
-        .filter_map(Result::ok)
-        .map(|entry| entry.path())
-        // Keeps only files
-        .filter(|path| path.is_file())
-        // Converts to ColorschemeInfo structs
-        .map(|path| ColorschemeInfo::new(&path))
-        .filter_map(Result::ok)
-        // Filters based on the luminance
+    let mut colorscheme_infos: Vec<_> = colorscheme_paths()
+        .map_err(SystemError)?
+        .into_iter()
+        // Filters based on the directory
+        .filter(|path| match dir {
+            Some(dir) => colorscheme_name(path).starts_with(&format!("{}/", dir)),
+            None => true,
+        })
+        // Converts to ColorschemeInfo structs, skipping the invalid ones
+        .filter_map(|path| match ColorschemeInfo::new(&path) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                log_as_warning(ColorschemeError(format!(
+                    "Skipping `{}`. {}. Run `{} check --all` for details",
+                    path.display(),
+                    e.trim_end(),
+                    info::APP_NAME
+                )));
+                None
+            }
+        })
+        // Filters based on the variant
         .filter(|c| {
-            if (dark_only && c.background_luminance >= 0.5)
-                || (light_only && c.background_luminance < 0.5)
+            if (dark_only && c.variant != Variant::Dark)
+                || (light_only && c.variant != Variant::Light)
             {
                 false
             } else {
                 true
             }
         })
+        // Filters based on the tags
+        .filter(|c| tags.iter().all(|tag| c.meta.has_tag(tag)))
         .collect();
 
     // Sorts colorschemes according to the --sort-by flag
@@ -87,7 +142,8 @@ pub fn list(args: &ArgMatches) -> Result<(), Error> {
         .unwrap();
     match sort_by.as_str() {
         "name" | "n" => colorscheme_infos.sort_by(|a, b| a.name.cmp(&b.name)),
-        "background_luminance"
+        "background-luminance"
+        | "background_luminance"
         | "bg-lum"
         | "bg_lum"
         | "bglum"
@@ -112,36 +168,201 @@ pub fn list(args: &ArgMatches) -> Result<(), Error> {
         _ => unreachable!(),
     }
 
+    // Rows of the table: either one per colorscheme, or the directory
+    // tree with a row per directory too
+    let rows: Vec<(String, Option<&ColorschemeInfo>)> = match args.get_flag("tree") {
+        true => tree_rows(&colorscheme_infos),
+        false => colorscheme_infos
+            .iter()
+            .map(|c| (c.name.clone(), Some(c)))
+            .collect(),
+    };
+
     // Finds the length of the longest colorscheme name
-    let max_len: usize = colorscheme_infos
+    let max_len: usize = rows
         .iter()
-        .map(|c| c.name.chars().count())
+        .map(|(name, _)| name.chars().count())
         .max()
-        .unwrap();
+        .unwrap_or(0)
+        .max("NAME".len());
+
+    // Headers and widths of the optional columns
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|column| match *column {
+            "name" => "DISPLAY NAME".to_string(),
+            column => column.to_uppercase(),
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .zip(&headers)
+        .map(|(column, header)| {
+            colorscheme_infos
+                .iter()
+                .map(|c| c.column(column).chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(header.len())
+        })
+        .collect();
 
     // Prints the headline
-    println!(
+    let mut headline = format!(
         "│ {:<width$} │ {:<8} │ {:<4} │",
         "NAME",
         "LUM (BG)",
         "CONT",
         width = max_len
     );
+    for (header, width) in headers.iter().zip(&widths) {
+        headline.push_str(&format!(" {:<width$} │", header, width = width));
+    }
+    println!("{}", headline);
 
     // Prints the colorschemes
-    for colorscheme in colorscheme_infos {
-        let _ = print_with_custom_colors(
-            &colorscheme.background,
-            &colorscheme.foreground,
-            format!(
-                "│ {:<width$} │ {:<8.2} │ {:<4.2} │\n",
-                colorscheme.name,
-                colorscheme.background_luminance,
-                colorscheme.contrast,
-                width = max_len,
-            ),
+    for (name, colorscheme) in rows {
+        // Directories have no colors
+        let colorscheme = match colorscheme {
+            Some(colorscheme) => colorscheme,
+            None => {
+                let mut line = format!(
+                    "│ {:<width$} │ {:<8} │ {:<4} │",
+                    name,
+                    "",
+                    "",
+                    width = max_len
+                );
+                for width in &widths {
+                    line.push_str(&format!(" {:<width$} │", "", width = width));
+                }
+                println!("{}", line);
+                continue;
+            }
+        };
+
+        let mut line = format!(
+            "│ {:<width$} │ {:<8.2} │ {:<4.2} │",
+            name,
+            colorscheme.background_luminance,
+            colorscheme.contrast,
+            width = max_len,
         );
+        for (column, width) in columns.iter().zip(&widths) {
+            line.push_str(&format!(
+                " {:<width$} │",
+                colorscheme.column(column),
+                width = width
+            ));
+        }
+        line.push('\n');
+
+        let _ = print_with_custom_colors(&colorscheme.background, &colorscheme.foreground, line);
     }
 
     Ok(())
 }
+
+// Directory of the colorschemes tree
+#[derive(Default)]
+struct TreeNode<'a> {
+    dirs: BTreeMap<&'a str, TreeNode<'a>>,
+    colorschemes: Vec<(&'a str, &'a ColorschemeInfo)>,
+}
+
+// Rows of the `--tree` view: each directory is followed by its
+// subdirectories, then by its colorschemes in the chosen order
+fn tree_rows(colorscheme_infos: &[ColorschemeInfo]) -> Vec<(String, Option<&ColorschemeInfo>)> {
+    let mut root = TreeNode::default();
+    for info in colorscheme_infos {
+        let mut components: Vec<&str> = info.name.split('/').collect();
+        let file_name = components.pop().unwrap();
+
+        let node = components
+            .into_iter()
+            .fold(&mut root, |node, dir| node.dirs.entry(dir).or_default());
+        node.colorschemes.push((file_name, info));
+    }
+
+    let mut rows = Vec::new();
+    push_tree_rows(&root, "", true, &mut rows);
+
+    rows
+}
+
+fn push_tree_rows<'a>(
+    node: &TreeNode<'a>,
+    prefix: &str,
+    is_root: bool,
+    rows: &mut Vec<(String, Option<&'a ColorschemeInfo>)>,
+) {
+    let count = node.dirs.len() + node.colorschemes.len();
+    let children = node
+        .dirs
+        .iter()
+        .map(|(dir, child)| (format!("{}/", dir), Some(child), None))
+        .chain(
+            node.colorschemes
+                .iter()
+                .map(|(name, info)| (name.to_string(), None, Some(*info))),
+        );
+
+    for (i, (label, child, info)) in children.enumerate() {
+        let is_last = i + 1 == count;
+
+        // The top level has no branches
+        let (branch, indent) = match (is_root, is_last) {
+            (true, _) => ("", ""),
+            (false, false) => ("├── ", "│   "),
+            (false, true) => ("└── ", "    "),
+        };
+        rows.push((format!("{}{}{}", prefix, branch, label), info));
+
+        if let Some(child) = child {
+            push_tree_rows(child, &format!("{}{}", prefix, indent), false, rows);
+        }
+    }
+}
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree() {
+        let info = |name: &str| ColorschemeInfo {
+            name: name.to_string(),
+            background: RGB(0, 0, 0),
+            foreground: RGB(255, 255, 255),
+            background_luminance: 0.0,
+            contrast: 1.0,
+            variant: Variant::Dark,
+            variant_names: Vec::new(),
+            meta: Meta::default(),
+            source: String::new(),
+        };
+        let infos = [
+            info("catppuccin/mocha"),
+            info("gruvbox"),
+            info("catppuccin/latte"),
+            info("catppuccin/extra/frappe"),
+        ];
+
+        let rows: Vec<(String, bool)> = tree_rows(&infos)
+            .into_iter()
+            .map(|(label, info)| (label, info.is_some()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("catppuccin/".to_string(), false),
+                ("├── extra/".to_string(), false),
+                ("│   └── frappe".to_string(), true),
+                ("├── mocha".to_string(), true),
+                ("└── latte".to_string(), true),
+                ("gruvbox".to_string(), true),
+            ]
+        );
+    }
+}
@@ -1,25 +1,32 @@
 use crate::blueprint::build_blueprints;
 use crate::color_test_table::print_color_test_table;
 use crate::colortable::ColorTable;
-use crate::config::blueprint::directive::HEX_6_DIGITS_W_HASHTAG;
-use crate::config::colorscheme::FILE_EXTENSION;
-use crate::config::environ::{COLORSCHEMES_DIR, CURRENT_COLORSCHEME_FILE};
+use crate::config::environ::{CURRENT_COLORSCHEME_FILE, CURRENT_COLORSCHEME_NAME_FILE};
 use crate::logging::{log_as_warning, Error};
+use crate::util::{read_file, search_colorscheme};
 use clap::ArgMatches;
-use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 
 pub fn load(args: &ArgMatches) -> Result<(), Error> {
-    // Complete file path
     let colorscheme_name = args.get_one::<String>("colorscheme").unwrap();
-    let path = (&*COLORSCHEMES_DIR).join(format!("{}.{}", colorscheme_name, FILE_EXTENSION));
+    let variant = args.get_one::<String>("variant").map(String::as_str);
+
+    load_colorscheme(colorscheme_name, variant)
+}
+
+// Loads a colorscheme, optionally one of its variants, and instantiates
+// all the blueprints
+pub fn load_colorscheme(colorscheme_name: &str, variant: Option<&str>) -> Result<(), Error> {
+    // Complete file path
+    let path = search_colorscheme(colorscheme_name).map_err(Error::ColorschemeError)?;
 
     // Loads the colorscheme
-    let mut colors = ColorTable::from_file_path(&path).map_err(|e| Error::ColorschemeError(e))?;
+    let mut colors = ColorTable::from_file_path_with_variant(&path, variant)
+        .map_err(|e| Error::ColorschemeError(e))?;
 
     // Stores a copy of the current colorscheme
-    let result = backup_colorscheme(&colors).map_err(|e| {
+    let result = backup_colorscheme(colorscheme_name, &colors).map_err(|e| {
         Error::SystemError(format!(
             "While storing a copy of the current colorscheme in `{}`. {}",
             CURRENT_COLORSCHEME_FILE.display(),
@@ -40,20 +47,9 @@ pub fn load(args: &ArgMatches) -> Result<(), Error> {
     Ok(())
 }
 
-fn backup_colorscheme(colors: &ColorTable) -> Result<(), String> {
-    // Replaces RGB colors with hexadecimal color strings
-    let hex_colors: HashMap<String, String> = colors
-        .iter()
-        .map(|(name, rgb)| {
-            (
-                name.clone(),
-                rgb.format(&HEX_6_DIGITS_W_HASHTAG.to_string()).unwrap(),
-            )
-        })
-        .collect();
-
-    // Converts the hash map to a json string
-    let json_string = serde_json::to_string(&hex_colors).map_err(|e| e.to_string())?;
+fn backup_colorscheme(name: &str, colors: &ColorTable) -> Result<(), String> {
+    // Converts the colorscheme to a json string
+    let json_string = serde_json::to_string(colors).map_err(|e| e.to_string())?;
 
     // Stores the colorscheme
     let mut file = File::create(&*CURRENT_COLORSCHEME_FILE).map_err(|e| e.to_string())?;
@@ -61,5 +57,25 @@ fn backup_colorscheme(colors: &ColorTable) -> Result<(), String> {
     file.write_all(json_string.as_bytes())
         .map_err(|e| e.to_string())?;
 
+    // Stores its name and variant, so that `reload` can read it again
+    let mut name = name.to_string();
+    if let Some(variant) = colors.variant_name() {
+        name.push('\n');
+        name.push_str(variant);
+    }
+    fs::write(&*CURRENT_COLORSCHEME_NAME_FILE, name).map_err(|e| e.to_string())?;
+
     Ok(())
 }
+
+// Name and variant of the latest colorscheme loaded, as stored by
+// backup_colorscheme
+pub fn current_colorscheme() -> Option<(String, Option<String>)> {
+    let content = read_file(&CURRENT_COLORSCHEME_NAME_FILE).ok()?;
+    let mut lines = content.lines().map(str::trim);
+
+    let name = lines.next().filter(|name| !name.is_empty())?.to_string();
+    let variant = lines.next().filter(|variant| !variant.is_empty());
+
+    Some((name, variant.map(str::to_string)))
+}
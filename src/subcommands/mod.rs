@@ -1,9 +1,21 @@
+mod check;
+mod export;
+mod generate;
+mod import;
 mod list;
 mod load;
 mod preview;
 mod reload;
+mod schema;
+mod toggle_variant;
 
+pub use check::check;
+pub use export::export;
+pub use generate::generate;
+pub use import::import;
 pub use list::list;
 pub use load::load;
 pub use preview::preview;
 pub use reload::reload;
+pub use schema::schema;
+pub use toggle_variant::toggle_variant;
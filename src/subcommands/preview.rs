@@ -1,19 +1,43 @@
 use crate::color_test_table::print_color_test_table;
 use crate::colortable::ColorTable;
-use crate::config::colorscheme::FILE_EXTENSION;
-use crate::config::environ::COLORSCHEMES_DIR;
 use crate::logging::Error;
+use crate::util::search_colorscheme;
 use clap::ArgMatches;
+use std::collections::BTreeMap;
+use std::path::Path;
 
 pub fn preview(args: &ArgMatches) -> Result<(), Error> {
     // Complete file path
     let colorscheme_name = args.get_one::<String>("colorscheme").unwrap();
-    let path = (&*COLORSCHEMES_DIR).join(format!("{}.{}", colorscheme_name, FILE_EXTENSION));
+    let path = search_colorscheme(colorscheme_name).map_err(Error::ColorschemeError)?;
 
     // Loads the colorscheme
-    let colors = ColorTable::from_file_path(&path).map_err(|e| Error::ColorschemeError(e))?;
+    let variant = args.get_one::<String>("variant").map(String::as_str);
+    let colors = ColorTable::from_file_path_with_variant(&path, variant)
+        .map_err(|e| Error::ColorschemeError(e))?;
 
     print_color_test_table(&colors);
+    print_inherited_colors(&colors, &path);
 
     Ok(())
 }
+
+// Lists the colors inherited through `extends`, grouped by the file
+// they come from
+fn print_inherited_colors(colors: &ColorTable, path: &Path) {
+    let mut origins: BTreeMap<&Path, Vec<&str>> = BTreeMap::new();
+    for name in colors.keys() {
+        if let Some(origin) = colors.origin(name).filter(|&origin| origin != path) {
+            origins.entry(origin).or_default().push(name);
+        }
+    }
+
+    for (origin, mut names) in origins {
+        names.sort();
+        println!(
+            "Inherited from `{}`: {}",
+            origin.display(),
+            names.join(", ")
+        );
+    }
+}
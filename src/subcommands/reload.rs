@@ -1,18 +1,17 @@
+use super::load::current_colorscheme;
 use crate::blueprint::{build_blueprint, build_blueprints};
 use crate::colortable::ColorTable;
-use crate::config::environ::{
-    CACHE_BLUEPRINTS_DIR, CONFIG_BLUEPRINTS_DIR, CURRENT_COLORSCHEME_FILE, POST_EXEC_SCRIPT,
-};
-use crate::logging::log_as_error;
-use crate::logging::Error::{self, BlueprintError, ExecError, SystemError};
+use crate::config::environ::{BLUEPRINTS_DIRS, CURRENT_COLORSCHEME_FILE, POST_EXEC_SCRIPT};
+use crate::logging::Error::{self, BlueprintError, ColorschemeError, ExecError, SystemError};
+use crate::logging::{log_as_error, log_as_warning};
+use crate::util::search_colorscheme;
 use clap::ArgMatches;
 use std::path::PathBuf;
 use std::process::Command;
 
 pub fn reload(args: &ArgMatches) -> Result<(), Error> {
     // Loads the colorscheme
-    let mut colors = ColorTable::from_file_path(&*CURRENT_COLORSCHEME_FILE)
-        .map_err(|e| Error::ColorschemeError(e))?;
+    let mut colors = load_current_colorscheme()?;
 
     // Instantiates all the blueprints
     build_selected_blueprints(&args, &mut colors)?;
@@ -27,6 +26,28 @@ pub fn reload(args: &ArgMatches) -> Result<(), Error> {
     Ok(())
 }
 
+// Reads the latest colorscheme again from its file, so that the changes
+// made since `load` are applied. Falls back to the copy stored by `load`
+// when the colorscheme can't be found anymore
+fn load_current_colorscheme() -> Result<ColorTable, Error> {
+    if let Some((name, variant)) = current_colorscheme() {
+        let colors = search_colorscheme(&name)
+            .and_then(|path| ColorTable::from_file_path_with_variant(&path, variant.as_deref()));
+
+        match colors {
+            Ok(colors) => return Ok(colors),
+            Err(e) => log_as_warning(ColorschemeError(format!(
+                "Can't reload colorscheme `{}`, using the copy stored in `{}`. {}",
+                name,
+                CURRENT_COLORSCHEME_FILE.display(),
+                e
+            ))),
+        }
+    }
+
+    ColorTable::from_file_path(&CURRENT_COLORSCHEME_FILE).map_err(ColorschemeError)
+}
+
 fn build_selected_blueprints(args: &ArgMatches, colors: &mut ColorTable) -> Result<(), Error> {
     let blueprints = args.get_many::<String>("blueprint");
 
@@ -53,11 +74,11 @@ fn build_selected_blueprints(args: &ArgMatches, colors: &mut ColorTable) -> Resu
 }
 
 fn search_blueprint(blueprint: &String) -> Result<PathBuf, String> {
-    let paths = [
-        CONFIG_BLUEPRINTS_DIR.join(blueprint),
-        CACHE_BLUEPRINTS_DIR.join(blueprint),
-        PathBuf::from(blueprint),
-    ];
+    let paths: Vec<PathBuf> = BLUEPRINTS_DIRS
+        .iter()
+        .map(|dir| dir.join(blueprint))
+        .chain([PathBuf::from(blueprint)])
+        .collect();
 
     for path in &paths {
         if path.exists() {
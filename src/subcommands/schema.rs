@@ -0,0 +1,18 @@
+use crate::colortable::schema::json_schema;
+use crate::logging::Error::{self, SystemError};
+use clap::ArgMatches;
+use std::fs;
+
+pub fn schema(args: &ArgMatches) -> Result<(), Error> {
+    let mut schema = serde_json::to_string_pretty(&json_schema()).unwrap();
+    schema.push('\n');
+
+    // Writes either to a file or to stdout
+    match args.get_one::<String>("output") {
+        Some(output) => fs::write(output, schema)
+            .map_err(|e| SystemError(format!("Can't write `{}`. {}", output, e)))?,
+        None => print!("{}", schema),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use super::load::{current_colorscheme, load_colorscheme};
+use crate::colortable::ColorTable;
+use crate::config::info::APP_NAME;
+use crate::logging::Error::{self, ColorschemeError};
+use crate::util::search_colorscheme;
+use clap::ArgMatches;
+
+// Loads the sibling variant of the current colorscheme, i.e. the next one
+// in the order of the file (e.g. `light` when `dark` is loaded)
+pub fn toggle_variant(_args: &ArgMatches) -> Result<(), Error> {
+    let (name, variant) = current_colorscheme().ok_or(ColorschemeError(format!(
+        "No colorscheme was loaded yet. Run `{} load` first",
+        APP_NAME
+    )))?;
+
+    // The variants are read again, since the file may have changed
+    let path = search_colorscheme(&name).map_err(ColorschemeError)?;
+    let colors = ColorTable::from_file_path(&path).map_err(ColorschemeError)?;
+    let variant_names = colors.variant_names();
+
+    if variant_names.len() < 2 {
+        return Err(ColorschemeError(format!(
+            "Colorscheme `{}` has no sibling variant to switch to",
+            name
+        )));
+    }
+
+    let next = match variant_names
+        .iter()
+        .position(|v| Some(v) == variant.as_ref())
+    {
+        Some(index) => &variant_names[(index + 1) % variant_names.len()],
+        None => &variant_names[0],
+    };
+
+    load_colorscheme(&name, Some(next))
+}
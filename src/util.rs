@@ -1,7 +1,13 @@
-use crate::config::environ::HOME_DIR;
-use std::fs::File;
+use crate::colortable::format::Format;
+use crate::config::colorscheme::FILE_EXTENSIONS;
+use crate::config::environ::{COLORSCHEMES_DIR, COLORSCHEMES_DIRS, HOME_DIR};
+use crate::logging::log_as_warning;
+use crate::logging::Error::SystemError;
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::fs::{read_dir, File};
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn read_file<'a>(path: &PathBuf) -> Result<String, String> {
     // Opens the file
@@ -27,6 +33,182 @@ pub fn expand_home_dir(path: &str) -> PathBuf {
     expanded_path
 }
 
+// Paths of all the colorschemes inside COLORSCHEMES_DIRS and their
+// subdirectories, i.e. the files with a supported extension, sorted by
+// name. Hidden files and directories are skipped, and so are the
+// colorschemes shadowed by a directory with higher priority. Unreadable
+// directories are skipped with a warning
+pub fn colorscheme_paths() -> Result<Vec<PathBuf>, String> {
+    let mut names: HashSet<String> = HashSet::new();
+    let mut paths: Vec<(String, PathBuf)> = Vec::new();
+    for dir in COLORSCHEMES_DIRS.iter() {
+        // Only the user directory is required to exist
+        if !dir.is_dir() {
+            if *dir == *COLORSCHEMES_DIR {
+                return Err(format!("Can't find directory `{}`", dir.display()));
+            }
+            continue;
+        }
+
+        let mut dir_paths = Vec::new();
+        collect_colorscheme_paths(dir, &mut dir_paths);
+        dir_paths.sort();
+
+        for path in dir_paths {
+            let name = colorscheme_name(&path);
+            if names.insert(name.clone()) {
+                paths.push((name, path));
+            }
+        }
+    }
+    paths.sort();
+
+    Ok(paths.into_iter().map(|(_, path)| path).collect())
+}
+
+fn collect_colorscheme_paths(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let entries = match read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log_as_warning(SystemError(format!(
+                "Can't read `{}`. {}",
+                dir.display(),
+                e
+            )));
+            return;
+        }
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        // Symlinks to directories aren't followed, to avoid loops
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_colorscheme_paths(&path, paths);
+        } else if path.is_file() && Format::from_path(&path).is_ok() {
+            paths.push(path);
+        }
+    }
+}
+
+// Directory of COLORSCHEMES_DIRS a colorscheme comes from
+pub fn colorscheme_source(path: &Path) -> Option<&'static Path> {
+    COLORSCHEMES_DIRS
+        .iter()
+        .find(|dir| path.starts_with(dir))
+        .map(PathBuf::as_path)
+}
+
+// Name of a colorscheme: its path relative to the directory of
+// COLORSCHEMES_DIRS it comes from, without extension and with `/`
+// separated components (e.g. `catppuccin/mocha`). Colorschemes outside
+// those directories are named after their file
+pub fn colorscheme_name(path: &Path) -> String {
+    match colorscheme_source(path).and_then(|dir| path.strip_prefix(dir).ok()) {
+        Some(relative) => relative
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        None => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+// Checks that a colorscheme name is a relative path made of `/`
+// separated components, so that it can't point outside COLORSCHEMES_DIRS
+pub fn validate_colorscheme_name(name: &str) -> Result<(), String> {
+    let is_valid = name
+        .split('/')
+        .all(|c| !c.is_empty() && c != "." && c != ".." && !c.contains('\\'));
+
+    match is_valid {
+        true => Ok(()),
+        false => Err(format!(
+            "Invalid colorscheme name `{}`. Use `/` to separate directories, e.g. `catppuccin/mocha`",
+            name
+        )),
+    }
+}
+
+// Looks for the colorscheme `name` (e.g. `gruvbox` or `catppuccin/mocha`)
+// inside COLORSCHEMES_DIRS. It's the name resolution shared by all the
+// subcommands
+pub fn search_colorscheme(name: &str) -> Result<PathBuf, String> {
+    let paths = search_colorscheme_all(name)?;
+    if let Some(path) = paths.into_iter().next() {
+        return Ok(path);
+    }
+
+    // Suggests the colorschemes of a directory
+    let names: Vec<String> = colorscheme_paths()
+        .unwrap_or_default()
+        .iter()
+        .map(|path| colorscheme_name(path))
+        .filter(|other| {
+            other
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .is_some_and(|rest| !rest.contains('/'))
+        })
+        .collect();
+
+    if !names.is_empty() {
+        return Err(format!(
+            "Can't find colorscheme `{}`, it's a directory. Did you mean `{}`?",
+            name,
+            names.join("`, `")
+        ));
+    }
+
+    Err(format!(
+        "Can't find colorscheme `{}`. None of the directories `{}` holds a `{}` file with extension `{}`",
+        name,
+        COLORSCHEMES_DIRS
+            .iter()
+            .map(|p| p.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("`, `"),
+        name,
+        FILE_EXTENSIONS.join("`, `")
+    ))
+}
+
+// Every file matching the colorscheme `name`, by decreasing priority: one
+// per directory of COLORSCHEMES_DIRS, trying each of the supported file
+// extensions in order. The ones after the first are shadowed, but they
+// can still be extended by the first one
+pub fn search_colorscheme_all(name: &str) -> Result<Vec<PathBuf>, String> {
+    validate_colorscheme_name(name)?;
+
+    let paths = COLORSCHEMES_DIRS
+        .iter()
+        .filter_map(|dir| {
+            FILE_EXTENSIONS
+                .iter()
+                .map(|extension| dir.join(format!("{}.{}", name, extension)))
+                .find(|path| path.is_file())
+        })
+        .collect();
+
+    Ok(paths)
+}
+
+// Names of the values of a command line enum, without their aliases
+pub fn value_names<T: ValueEnum>() -> Vec<String> {
+    T::value_variants()
+        .iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
 use crate::colortable::rgb::RGB;
 use std::io::{self, Write};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
@@ -53,3 +235,34 @@ pub fn print_with_custom_colors(bg: &RGB, fg: &RGB, text: String) -> io::Result<
 
     Ok(())
 }
+
+// Test module
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorscheme_names() {
+        assert!(validate_colorscheme_name("gruvbox").is_ok());
+        assert!(validate_colorscheme_name("catppuccin/mocha").is_ok());
+        assert!(validate_colorscheme_name("").is_err());
+        assert!(validate_colorscheme_name("/etc/passwd").is_err());
+        assert!(validate_colorscheme_name("../gruvbox").is_err());
+        assert!(validate_colorscheme_name("catppuccin//mocha").is_err());
+        assert!(validate_colorscheme_name("catppuccin/").is_err());
+
+        let path = COLORSCHEMES_DIR.join("catppuccin").join("mocha.json");
+        assert_eq!(colorscheme_name(&path), "catppuccin/mocha");
+        assert_eq!(colorscheme_name(Path::new("/tmp/gruvbox.toml")), "gruvbox");
+    }
+
+    #[test]
+    fn colorscheme_sources() {
+        // The user directory comes first
+        assert_eq!(COLORSCHEMES_DIRS.first(), Some(&*COLORSCHEMES_DIR));
+
+        let path = COLORSCHEMES_DIR.join("gruvbox.json");
+        assert_eq!(colorscheme_source(&path), Some(COLORSCHEMES_DIR.as_path()));
+        assert_eq!(colorscheme_source(Path::new("/tmp/gruvbox.json")), None);
+    }
+}